@@ -0,0 +1,60 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use cards::*;
+use rand::prelude::IndexedRandom;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const ROUNDS: u32 = 200;
+
+// The shuffle Deck used before Fisher-Yates, kept here to compare against
+fn quadratic_shuffle<R: Rng + ?Sized>(deck: &Deck, rng: &mut R) -> Deck {
+    let mut nums: Vec<i32> = (0..deck.cards.len() as i32).collect();
+    let mut new_deck: Vec<Card> = vec![Card::new(Value::Nil, Suit::Nil, false); deck.cards.len()];
+
+    for card in &deck.cards {
+        if let Some(random_element) = nums.choose(rng) {
+            new_deck[*random_element as usize] = *card;
+            nums.remove(nums.iter().position(|x| x == random_element).unwrap());
+        }
+    }
+
+    Deck { cards: new_deck }
+}
+
+fn time(mut f: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        f();
+    }
+    start.elapsed() / ROUNDS
+}
+
+fn main() {
+    println!(
+        "{:<8} {:>14} {:>14} {:>10}",
+        "decks", "quadratic", "fisher-yates", "speedup"
+    );
+
+    for num_decks in [1, 2, 6, 8] {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut deck = Deck::new(num_decks);
+
+        let old = time(|| {
+            black_box(quadratic_shuffle(black_box(&deck), &mut rng));
+        });
+        let new = time(|| {
+            deck.shuffle_with(&mut rng);
+            black_box(&deck);
+        });
+
+        println!(
+            "{:<8} {:>14?} {:>14?} {:>9.1}x",
+            num_decks,
+            old,
+            new,
+            old.as_secs_f64() / new.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{Card, Value};
+
+// The thirteen ranks of one deck
+const DECK: [Value; 13] = [
+    Value::Ace,
+    Value::Two,
+    Value::Three,
+    Value::Four,
+    Value::Five,
+    Value::Six,
+    Value::Seven,
+    Value::Eight,
+    Value::Nine,
+    Value::Ten,
+    Value::Jack,
+    Value::Queen,
+    Value::King,
+];
+
+// A card counting system: a tag for every value, added up as cards are seen
+pub trait CountingSystem: Sync {
+    fn name(&self) -> &'static str;
+    fn tag(&self, value: Value) -> f32;
+
+    // Unbalanced systems start below zero so the count reaches the same
+    // point whatever the number of decks
+    fn initial_count(&self, _num_decks: u32) -> f32 {
+        0.0
+    }
+
+    fn count(&self, cards: &[Card]) -> f32 {
+        cards.iter().map(|card| self.tag(card.value)).sum()
+    }
+
+    // What a whole deck adds up to, which is 0 for a balanced system
+    fn deck_total(&self) -> f32 {
+        DECK.iter().map(|value| 4.0 * self.tag(*value)).sum()
+    }
+
+    // Only a balanced system has a true count worth working out
+    fn balanced(&self) -> bool {
+        self.deck_total() == 0.0
+    }
+}
+
+// The running count per deck still to come. With less than a card left
+// there's nothing to divide by, so it's just the running count.
+pub fn true_count(running_count: f32, decks_remaining: f32) -> f32 {
+    if decks_remaining * 52.0 < 1.0 {
+        running_count
+    } else {
+        running_count / decks_remaining
+    }
+}
+
+pub struct HiLo;
+pub struct KnockOut;
+pub struct OmegaII;
+pub struct Zen;
+pub struct WongHalves;
+
+pub const SYSTEMS: [&dyn CountingSystem; 5] = [&HiLo, &KnockOut, &OmegaII, &Zen, &WongHalves];
+
+pub fn counting_system(name: &str) -> Option<&'static dyn CountingSystem> {
+    SYSTEMS
+        .into_iter()
+        .find(|system| system.name().eq_ignore_ascii_case(name))
+}
+
+impl CountingSystem for HiLo {
+    fn name(&self) -> &'static str {
+        "Hi-Lo"
+    }
+
+    fn tag(&self, value: Value) -> f32 {
+        match value {
+            Value::Two | Value::Three | Value::Four | Value::Five | Value::Six => 1.0,
+            Value::Seven | Value::Eight | Value::Nine | Value::Nil => 0.0,
+            Value::Ten | Value::Jack | Value::Queen | Value::King | Value::Ace => -1.0,
+        }
+    }
+}
+
+impl CountingSystem for KnockOut {
+    fn name(&self) -> &'static str {
+        "KO"
+    }
+
+    fn tag(&self, value: Value) -> f32 {
+        match value {
+            Value::Two | Value::Three | Value::Four | Value::Five | Value::Six | Value::Seven => {
+                1.0
+            }
+            Value::Eight | Value::Nine | Value::Nil => 0.0,
+            Value::Ten | Value::Jack | Value::Queen | Value::King | Value::Ace => -1.0,
+        }
+    }
+
+    fn initial_count(&self, num_decks: u32) -> f32 {
+        4.0 - 4.0 * num_decks as f32
+    }
+}
+
+impl CountingSystem for OmegaII {
+    fn name(&self) -> &'static str {
+        "Omega II"
+    }
+
+    fn tag(&self, value: Value) -> f32 {
+        match value {
+            Value::Two | Value::Three | Value::Seven => 1.0,
+            Value::Four | Value::Five | Value::Six => 2.0,
+            Value::Eight | Value::Ace | Value::Nil => 0.0,
+            Value::Nine => -1.0,
+            Value::Ten | Value::Jack | Value::Queen | Value::King => -2.0,
+        }
+    }
+}
+
+impl CountingSystem for Zen {
+    fn name(&self) -> &'static str {
+        "Zen"
+    }
+
+    fn tag(&self, value: Value) -> f32 {
+        match value {
+            Value::Two | Value::Three | Value::Seven => 1.0,
+            Value::Four | Value::Five | Value::Six => 2.0,
+            Value::Eight | Value::Nine | Value::Nil => 0.0,
+            Value::Ace => -1.0,
+            Value::Ten | Value::Jack | Value::Queen | Value::King => -2.0,
+        }
+    }
+}
+
+impl CountingSystem for WongHalves {
+    fn name(&self) -> &'static str {
+        "Wong Halves"
+    }
+
+    fn tag(&self, value: Value) -> f32 {
+        match value {
+            Value::Two | Value::Seven => 0.5,
+            Value::Three | Value::Four | Value::Six => 1.0,
+            Value::Five => 1.5,
+            Value::Eight | Value::Nil => 0.0,
+            Value::Nine => -0.5,
+            Value::Ten | Value::Jack | Value::Queen | Value::King | Value::Ace => -1.0,
+        }
+    }
+}
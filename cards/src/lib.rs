@@ -1,7 +1,12 @@
-use rand::prelude::IndexedRandom;
-use rand::rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng, rng};
+
+pub mod count;
+
+pub use count::*;
 
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Card {
     pub value: Value,
     pub suit: Suit,
@@ -35,6 +40,7 @@ impl Card {
 }
 
 #[derive(Clone, Debug, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Suit {
     Clubs,
     Hearts,
@@ -56,6 +62,7 @@ impl Suit {
 }
 
 #[derive(Clone, Debug, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Value {
     Ace,
     Two,
@@ -120,8 +127,8 @@ impl Deck {
                     Value::King,
                 ] {
                     cards.push(Card {
-                        suit: suit.clone(),
-                        value: value.clone(),
+                        suit,
+                        value,
                         hidden: false,
                     });
                 }
@@ -130,27 +137,123 @@ impl Deck {
         Self { cards }
     }
 
-    pub fn shuffle(&mut self) -> Deck {
-        let mut nums: Vec<i32> = (0..self.cards.len() as i32).collect();
-        let mut new_deck: Vec<Card> = vec![
-            Card {
-                value: Value::Nil,
-                suit: Suit::Nil,
-                hidden: false
-            };
-            self.cards.len()
-        ];
-
-        for card in &self.cards {
-            let mut rng = rng();
-            if let Some(random_element) = nums.choose(&mut rng) {
-                new_deck[random_element.clone() as usize] = *card;
-                nums.remove(nums.iter().position(|x| x == random_element).unwrap());
-            } else {
-                println!("Empty");
+    pub fn shuffle(&mut self) {
+        self.shuffle_with(&mut rng())
+    }
+
+    pub fn shuffle_seeded(&mut self, seed: u64) {
+        self.shuffle_with(&mut StdRng::seed_from_u64(seed))
+    }
+
+    pub fn shuffle_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        shuffle_cards(&mut self.cards, rng);
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Shoe {
+    pub cards: Vec<Card>,
+    pub discards: Vec<Card>,
+    num_decks: u32,
+    penetration: f32,
+    cut_card: usize,
+}
+
+impl Shoe {
+    pub fn new(num_decks: u32, penetration: f32) -> Self {
+        let mut shoe = Self {
+            cards: Deck::new(num_decks).cards,
+            discards: Vec::new(),
+            num_decks,
+            penetration: 0.0,
+            cut_card: 0,
+        };
+        shoe.set_penetration(penetration);
+        shoe
+    }
+
+    pub fn num_decks(&self) -> u32 {
+        self.num_decks
+    }
+
+    pub fn size(&self) -> usize {
+        self.num_decks as usize * 52
+    }
+
+    pub fn cards_left(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn penetration(&self) -> f32 {
+        self.penetration
+    }
+
+    // The cut card sits `penetration` of the way into the shoe, so 0.75 deals
+    // three quarters of the cards before calling for a reshuffle
+    pub fn set_penetration(&mut self, penetration: f32) {
+        self.penetration = penetration.clamp(0.0, 1.0);
+        let dealt = (self.size() as f32 * self.penetration).round() as usize;
+        self.cut_card = self.size() - dealt;
+    }
+
+    pub fn cut_card(&self) -> usize {
+        self.cut_card
+    }
+
+    pub fn needs_reshuffle(&self) -> bool {
+        self.cards.len() <= self.cut_card
+    }
+
+    pub fn decks_remaining(&self) -> f32 {
+        self.cards.len() as f32 / 52.0
+    }
+
+    // The count of everything that's come out of the shoe since the last
+    // shuffle, worked out from what's still in it
+    pub fn running_count(&self, system: &dyn CountingSystem) -> f32 {
+        let total = system.deck_total() * self.num_decks as f32;
+        system.initial_count(self.num_decks) + total - system.count(&self.cards)
+    }
+
+    pub fn true_count(&self, system: &dyn CountingSystem) -> f32 {
+        true_count(self.running_count(system), self.decks_remaining())
+    }
+
+    pub fn draw(&mut self) -> Option<Card> {
+        self.cards.pop()
+    }
+
+    pub fn discard(&mut self, cards: impl IntoIterator<Item = Card>) {
+        for mut card in cards {
+            if card.value == Value::Nil {
+                continue;
             }
+            card.hidden = false;
+            self.discards.push(card);
         }
+    }
+
+    pub fn shuffle(&mut self) {
+        self.shuffle_with(&mut rng())
+    }
+
+    pub fn shuffle_seeded(&mut self, seed: u64) {
+        self.shuffle_with(&mut StdRng::seed_from_u64(seed))
+    }
+
+    // Puts the discard tray back into the shoe and shuffles everything that
+    // isn't out on the table
+    pub fn shuffle_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.cards.append(&mut self.discards);
+        shuffle_cards(&mut self.cards, rng);
+    }
+}
 
-        Deck { cards: new_deck }
+// Fisher-Yates: walk backwards swapping each card with one at or before it
+fn shuffle_cards<R: Rng + ?Sized>(cards: &mut [Card], rng: &mut R) {
+    for i in (1..cards.len()).rev() {
+        let j = rng.random_range(0..=i);
+        cards.swap(i, j);
     }
 }
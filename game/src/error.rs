@@ -0,0 +1,88 @@
+use std::fmt;
+
+use Cards::Card;
+
+use crate::{Action, Phase};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Errs {
+    DeckExhausted,
+    InvalidCard(Card),
+    NoHiddenProvided,
+    HiddenProvided,
+    NoBankProvided,
+    BankProvided,
+    CannotHit,
+    CannotSplit,
+    CannotDouble,
+    CannotSurrender,
+    CannotInsure,
+    InvalidInsurance { amount: i32, max: i32 },
+    InvalidBet(i32),
+    OutsideLimits { bet: i32, min: i32, max: i32 },
+    InsufficientFunds { bank: i32, bet: i32 },
+    TooManySpots(usize),
+    NoHandToPlay,
+    AlreadySettled,
+    TableFull,
+    NoSuchSeat(usize),
+    CannotLeave,
+    OutOfTurn(usize),
+    IllegalAction { phase: Phase, action: Action },
+}
+
+impl fmt::Display for Errs {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Errs::DeckExhausted => write!(f, "the shoe has run out of cards"),
+            Errs::InvalidCard(card) => write!(
+                f,
+                "{:?} of {:?} isn't a card that can be played",
+                card.value, card.suit
+            ),
+            Errs::NoHiddenProvided => write!(f, "the dealer needs a hole card"),
+            Errs::HiddenProvided => write!(f, "only the dealer has a hole card"),
+            Errs::NoBankProvided => write!(f, "the player needs a bank"),
+            Errs::BankProvided => write!(f, "the dealer doesn't have a bank"),
+            Errs::CannotHit => write!(f, "this hand can't take another card"),
+            Errs::CannotSplit => write!(f, "this hand can't be split"),
+            Errs::CannotDouble => write!(f, "this hand can't be doubled"),
+            Errs::CannotSurrender => write!(f, "this hand can't be surrendered"),
+            Errs::CannotInsure => write!(f, "insurance isn't on offer"),
+            Errs::InvalidInsurance { amount, max } => write!(
+                f,
+                "insurance of ${} isn't allowed, it has to be between $1 and ${}",
+                amount, max
+            ),
+            Errs::InvalidBet(bet) => write!(f, "a bet of ${} isn't allowed", bet),
+            Errs::OutsideLimits { bet, min, max } if *max == i32::MAX => {
+                write!(f, "a bet of ${} is under the ${} minimum", bet, min)
+            }
+            Errs::OutsideLimits { bet, min, max } => write!(
+                f,
+                "a bet of ${} isn't allowed, bets here are ${} to ${}",
+                bet, min, max
+            ),
+            Errs::InsufficientFunds { bank, bet } => {
+                write!(f, "can't bet ${} with only ${} in the bank", bet, bank)
+            }
+            Errs::TooManySpots(max) => {
+                write!(f, "can't bet on more than {} boxes at once", max)
+            }
+            Errs::NoHandToPlay => write!(f, "there's no hand left to play"),
+            Errs::AlreadySettled => write!(f, "the round has already been settled"),
+            Errs::TableFull => write!(f, "all {} seats are taken", crate::MAX_SEATS),
+            Errs::NoSuchSeat(seat) => write!(f, "there's no seat {}", seat + 1),
+            Errs::CannotLeave => write!(
+                f,
+                "players can only leave between rounds, and someone has to stay"
+            ),
+            Errs::OutOfTurn(seat) => write!(f, "it isn't seat {}'s turn", seat + 1),
+            Errs::IllegalAction { phase, action } => {
+                write!(f, "can't {} during the {}", action, phase)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Errs {}
@@ -0,0 +1,59 @@
+use Cards::Card;
+
+use crate::Settlement;
+
+// Everything that happened at the table since the last deal, in order
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Event {
+    Shuffled,
+    PlayerCard {
+        seat: usize,
+        hand: usize,
+        card: Card,
+    },
+    DealerCard(Card),
+    HoleCard,
+    HoleCardRevealed(Card),
+    Hit {
+        seat: usize,
+        hand: usize,
+    },
+    Stood {
+        seat: usize,
+        hand: usize,
+    },
+    Split {
+        seat: usize,
+        hand: usize,
+    },
+    Doubled {
+        seat: usize,
+        hand: usize,
+    },
+    Surrendered {
+        seat: usize,
+        hand: usize,
+        refund: i32,
+    },
+    InsurancePlaced {
+        seat: usize,
+        hand: usize,
+        amount: i32,
+    },
+    InsuranceDeclined {
+        seat: usize,
+        hand: usize,
+    },
+    InsuranceSettled {
+        seat: usize,
+        hand: usize,
+        net: i32,
+    },
+    EvenMoney {
+        seat: usize,
+        hand: usize,
+        paid: i32,
+    },
+    Settled(Settlement),
+}
@@ -0,0 +1,119 @@
+use Cards::{Card, Value};
+
+use crate::Errs;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hand {
+    pub cards: Vec<Card>,
+    // The betting box the hand was dealt to. Split hands stay in their box.
+    pub spot: usize,
+    pub wager: i32,
+    pub insurance: i32,
+    pub split: bool,
+    pub split_aces: bool,
+    pub doubled: bool,
+    pub surrendered: bool,
+    pub even_money: bool,
+    pub finished: bool,
+}
+
+impl Hand {
+    pub fn new(cards: Vec<Card>, wager: i32) -> Self {
+        Self {
+            cards,
+            spot: 0,
+            wager,
+            insurance: 0,
+            split: false,
+            split_aces: false,
+            doubled: false,
+            surrendered: false,
+            even_money: false,
+            finished: false,
+        }
+    }
+
+    // Two cards to 21 after a split is only a 21, not a blackjack
+    pub fn value(&self) -> Result<HandValue, Errs> {
+        let mut value = HandValue::new(&self.cards)?;
+        value.is_blackjack &= !self.split;
+        Ok(value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HandValue {
+    pub total: i32,
+    // An ace is still being counted as 11
+    pub is_soft: bool,
+    pub is_blackjack: bool,
+    pub is_bust: bool,
+    pub is_pair: bool,
+}
+
+impl HandValue {
+    pub fn new(cards: &[Card]) -> Result<Self, Errs> {
+        let mut total = 0;
+        for card in cards {
+            match card.value {
+                Value::Ace => {
+                    total += 11;
+                }
+
+                Value::Two => {
+                    total += 2;
+                }
+
+                Value::Three => {
+                    total += 3;
+                }
+
+                Value::Four => {
+                    total += 4;
+                }
+
+                Value::Five => {
+                    total += 5;
+                }
+
+                Value::Six => {
+                    total += 6;
+                }
+
+                Value::Seven => {
+                    total += 7;
+                }
+
+                Value::Eight => {
+                    total += 8;
+                }
+
+                Value::Nine => {
+                    total += 9;
+                }
+
+                Value::Ten | Value::Jack | Value::King | Value::Queen => {
+                    total += 10;
+                }
+
+                Value::Nil => {
+                    return Err(Errs::InvalidCard(*card));
+                }
+            }
+        }
+
+        let mut aces = cards.iter().filter(|card| card.value == Value::Ace).count();
+        while total > 21 && aces > 0 {
+            total -= 10;
+            aces -= 1;
+        }
+
+        Ok(Self {
+            total,
+            is_soft: aces > 0,
+            is_blackjack: total == 21 && cards.len() == 2,
+            is_bust: total > 21,
+            is_pair: cards.len() == 2 && cards[0].value == cards[1].value,
+        })
+    }
+}
@@ -0,0 +1,203 @@
+use std::io::{self, Write};
+
+use Cards::Card;
+
+use crate::{Event, TableRules};
+
+// Who sat where when the cards came out, with their bank before they bet
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Seat {
+    pub name: String,
+    pub bank: i32,
+    pub bets: Vec<i32>,
+}
+
+// One round from the deal to the table being cleared
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Round {
+    pub number: usize,
+    pub rules: TableRules,
+    pub seats: Vec<Seat>,
+    pub events: Vec<Event>,
+}
+
+impl Round {
+    // The cards in the order they came out of the shoe. A peeked hole card
+    // is drawn with the deal but only seen once it's turned over.
+    pub fn cards(&self) -> Vec<Card> {
+        let revealed = self.events.iter().find_map(|event| match event {
+            Event::HoleCardRevealed(card) => Some(*card),
+            _ => None,
+        });
+
+        let mut peeked = false;
+        let mut cards = Vec::new();
+        for event in &self.events {
+            match *event {
+                Event::PlayerCard { card, .. } | Event::DealerCard(card) => cards.push(card),
+                Event::HoleCard => {
+                    peeked = true;
+                    cards.extend(revealed);
+                }
+                Event::HoleCardRevealed(card) if !peeked => cards.push(card),
+                _ => {}
+            }
+        }
+        cards
+    }
+}
+
+// Events as they happen, kept once for whoever is watching the table and
+// once for the history of the round in play
+#[derive(Default)]
+pub(crate) struct Log {
+    pub(crate) events: Vec<Event>,
+    pub(crate) round: Option<Round>,
+}
+
+impl Log {
+    pub(crate) fn push(&mut self, event: Event) {
+        if let Some(round) = self.round.as_mut() {
+            round.events.push(event);
+        }
+        self.events.push(event);
+    }
+}
+
+// One line per round
+#[cfg(feature = "serde")]
+pub fn write_jsonl(rounds: &[Round], out: &mut impl Write) -> io::Result<()> {
+    for round in rounds {
+        serde_json::to_writer(&mut *out, round)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+pub fn read_jsonl(input: impl io::BufRead) -> io::Result<Vec<Round>> {
+    let mut rounds = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let round = serde_json::from_str(&line).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", i + 1, e))
+        })?;
+        rounds.push(round);
+    }
+    Ok(rounds)
+}
+
+// One row per bet and per event, with the dealer's cards on rows with no seat
+pub fn write_csv(rounds: &[Round], out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "round,seat,player,hand,event,card,amount,outcome")?;
+    for round in rounds {
+        let name = |seat: usize| round.seats.get(seat).map_or("", |seat| seat.name.as_str());
+
+        for (seat, entry) in round.seats.iter().enumerate() {
+            for (spot, bet) in entry.bets.iter().enumerate() {
+                let row = [
+                    round.number.to_string(),
+                    seat.to_string(),
+                    entry.name.clone(),
+                    spot.to_string(),
+                    "bet".to_string(),
+                    String::new(),
+                    bet.to_string(),
+                    String::new(),
+                ];
+                write_row(out, &row)?;
+            }
+        }
+
+        for event in &round.events {
+            let (seat, hand, kind, card, amount, outcome) = match *event {
+                Event::Shuffled => (None, None, "shuffle", None, None, None),
+                Event::PlayerCard { seat, hand, card } => {
+                    (Some(seat), Some(hand), "card", Some(card), None, None)
+                }
+                Event::DealerCard(card) => (None, None, "dealer card", Some(card), None, None),
+                Event::HoleCard => (None, None, "hole card", None, None, None),
+                Event::HoleCardRevealed(card) => {
+                    (None, None, "hole card revealed", Some(card), None, None)
+                }
+                Event::Hit { seat, hand } => (Some(seat), Some(hand), "hit", None, None, None),
+                Event::Stood { seat, hand } => (Some(seat), Some(hand), "stand", None, None, None),
+                Event::Split { seat, hand } => (Some(seat), Some(hand), "split", None, None, None),
+                Event::Doubled { seat, hand } => {
+                    (Some(seat), Some(hand), "double", None, None, None)
+                }
+                Event::Surrendered { seat, hand, refund } => (
+                    Some(seat),
+                    Some(hand),
+                    "surrender",
+                    None,
+                    Some(refund),
+                    None,
+                ),
+                Event::InsurancePlaced { seat, hand, amount } => (
+                    Some(seat),
+                    Some(hand),
+                    "insurance",
+                    None,
+                    Some(amount),
+                    None,
+                ),
+                Event::InsuranceDeclined { seat, hand } => {
+                    (Some(seat), Some(hand), "decline", None, None, None)
+                }
+                Event::InsuranceSettled { seat, hand, net } => (
+                    Some(seat),
+                    Some(hand),
+                    "insurance settled",
+                    None,
+                    Some(net),
+                    None,
+                ),
+                Event::EvenMoney { seat, hand, paid } => {
+                    (Some(seat), Some(hand), "even money", None, Some(paid), None)
+                }
+                Event::Settled(settlement) => (
+                    Some(settlement.seat),
+                    Some(settlement.hand),
+                    "settle",
+                    None,
+                    Some(settlement.net),
+                    Some(settlement.outcome),
+                ),
+            };
+
+            let row = [
+                round.number.to_string(),
+                seat.map_or(String::new(), |seat| seat.to_string()),
+                seat.map_or("Dealer", name).to_string(),
+                hand.map_or(String::new(), |hand| hand.to_string()),
+                kind.to_string(),
+                card.map_or(String::new(), |card| card.short_label()),
+                amount.map_or(String::new(), |amount| amount.to_string()),
+                outcome.map_or(String::new(), |outcome| format!("{:?}", outcome)),
+            ];
+            write_row(out, &row)?;
+        }
+    }
+    Ok(())
+}
+
+// Quotes any field with a comma, quote or line break in it
+fn write_row(out: &mut impl Write, fields: &[String]) -> io::Result<()> {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect();
+    writeln!(out, "{}", fields.join(","))
+}
@@ -1,307 +1,1331 @@
-use std::io::{stdin, stdout, Write};
+use std::time::{Duration, Instant};
 
 use Cards::*;
-
-#[derive(Debug, PartialEq)]
-pub enum Errs {
-    CardOutOfRange,
-    SuitOutRange,
-    UnknownError,
-    NoHiddenProvided,
-    HiddenProvided,
-    NoBankProvided,
-    BankProvided,
-}
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+pub mod error;
+pub mod event;
+pub mod hand;
+pub mod history;
+pub mod outcome;
+pub mod phase;
+pub mod rules;
+pub mod sim;
+pub mod stats;
+pub mod strategy;
+
+pub use error::*;
+pub use event::*;
+pub use hand::*;
+pub use history::*;
+pub use outcome::*;
+pub use phase::*;
+pub use rules::*;
+pub use sim::*;
+pub use stats::*;
+pub use strategy::*;
 
 pub trait User: Sized {
     fn new() -> Self;
     fn deal(&mut self, deck: &mut Vec<Card>) -> Result<[Card; 2], Errs>;
     fn hit(&mut self, deck: &mut Vec<Card>) -> Result<Card, Errs>;
-    fn stand(&mut self, hand: &Vec<Card>) -> Result<u32, Errs>;
+    fn stand(&mut self, hand: &[Card]) -> Result<u32, Errs>;
     fn from(hand: Vec<Card>, hidden: Option<Card>, bank: Option<i32>) -> Result<Self, Errs>;
 }
 
+pub const MAX_SEATS: usize = 7;
+
 pub struct Game<'a> {
-    pub deck: Deck,
+    pub shoe: Shoe,
+    rules: TableRules,
     dealer: &'a mut Dealer,
-    player: &'a mut Player,
-    pot: i32,
-    side_pot: i32,
+    players: Vec<&'a mut Player>,
+    // Whose turn it is, or who's betting before the deal
+    seat: usize,
+    phase: Phase,
+    log: Log,
+    history: Vec<Round>,
+    started: Instant,
+    // When the last round was settled, or the game started
+    lap: Instant,
+    seed: u64,
+    rng: StdRng,
 }
 
 impl<'a> Game<'a> {
-    pub fn new(num_decks: u32, dealer: &'a mut Dealer, player: &'a mut Player) -> Self {
-        Self {
-            deck: Deck::new(num_decks),
+    pub fn new(rules: TableRules, dealer: &'a mut Dealer, player: &'a mut Player) -> Self {
+        Self::with_seed(rules, rand::random(), dealer, player)
+    }
+
+    pub fn with_seed(
+        rules: TableRules,
+        seed: u64,
+        dealer: &'a mut Dealer,
+        player: &'a mut Player,
+    ) -> Self {
+        let mut game = Self {
+            shoe: Shoe::new(rules.num_decks, rules.penetration),
+            rules,
             dealer,
-            player,
-            pot: 0,
-            side_pot: 0,
+            players: vec![player],
+            seat: 0,
+            phase: Phase::Betting,
+            log: Log::default(),
+            history: Vec::new(),
+            started: Instant::now(),
+            lap: Instant::now(),
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        };
+        game.shoe.shuffle_with(&mut game.rng);
+
+        // Cards can already be on the table when the dealer and player were
+        // built with hands, so pick the round up from there
+        if !game.dealer.hand.is_empty() || !game.players[0].hands.is_empty() {
+            game.begin_play();
         }
+        game
     }
 
-    pub fn shuffle(&mut self) {
-        self.deck = self.deck.shuffle();
+    pub fn phase(&self) -> Phase {
+        self.phase
     }
 
-    pub fn deal(&mut self) {
-        self.shuffle();
-
-        match self.dealer.deal(&mut self.deck.cards) {
-            Ok([c1, c2]) => {
-                self.dealer.hand.push(c1);
-                self.dealer.hidden = c2;
+    fn allows(&self, action: Action) -> bool {
+        match action {
+            Action::Bet => self.phase == Phase::Betting,
+            Action::Deal => self.phase == Phase::Betting,
+            Action::Hit | Action::Stand | Action::Double | Action::Split => {
+                self.phase == Phase::PlayerTurn
             }
-            Err(e) => {
-                println!("Errors occured: {:?}", e);
+            // Early surrender goes round with the insurance offers, late
+            // surrender waits until the dealer has peeked
+            Action::Surrender => {
+                self.phase == Phase::PlayerTurn
+                    || (self.phase == Phase::Insurance && self.rules.surrender == Surrender::Early)
             }
+            Action::Insurance | Action::EvenMoney | Action::Decline => {
+                self.phase == Phase::Insurance
+            }
+            Action::DealerHit => self.phase == Phase::DealerTurn,
+            Action::Settle => {
+                self.phase == Phase::DealerTurn
+                    || (self.phase == Phase::PlayerTurn && self.table_done())
+            }
+            // Only a settled round, or a table nobody has been dealt at yet
+            Action::Clear => {
+                self.phase == Phase::Settled || (self.phase == Phase::Betting && !self.cards_out())
+            }
+        }
+    }
+
+    fn cards_out(&self) -> bool {
+        !self.dealer.hand.is_empty()
+            || self.dealer.hidden.value != Value::Nil
+            || self.players.iter().any(|player| !player.hands.is_empty())
+    }
+
+    fn check(&self, action: Action) -> Result<(), Errs> {
+        if self.allows(action) {
+            Ok(())
+        } else {
+            Err(Errs::IllegalAction {
+                phase: self.phase,
+                action,
+            })
         }
+    }
 
-        match self.player.deal(&mut self.deck.cards) {
-            Ok([c1, c2]) => {
-                self.player.hand.extend([c1, c2]);
+    // Everything the current seat can do right now, in the order of Action::ALL
+    pub fn legal_actions(&self) -> Vec<Action> {
+        let player = &self.players[self.seat];
+        let playing = player.current < player.hands.len();
+
+        Action::ALL
+            .into_iter()
+            .filter(|&action| match action {
+                Action::Bet => self.allows(action) && player.bets.len() < self.rules.max_spots,
+                Action::Hit => self.can_hit().is_ok(),
+                Action::Stand => self.allows(action) && playing,
+                Action::Double => self.can_double().is_ok(),
+                Action::Split => self.can_split().is_ok(),
+                Action::Surrender => self.can_surrender().is_ok(),
+                Action::Insurance => self.can_insure() && self.max_insurance() > 0,
+                Action::EvenMoney => self.can_take_even_money(),
+                Action::Decline => self.allows(action) && self.has_offer(self.seat, player.current),
+                Action::DealerHit => self.allows(action) && self.dealer_should_hit() == Ok(true),
+                _ => self.allows(action),
+            })
+            .collect()
+    }
+
+    // Insurance, even money and early surrender are offered box by box round
+    // the table straight after the deal. Then the dealer peeks, and play
+    // starts from the first seat unless that turned up a blackjack.
+    fn begin_play(&mut self) {
+        self.offer_from(0, 0);
+    }
+
+    fn has_offer(&self, seat: usize, index: usize) -> bool {
+        let Some(hand) = self.players[seat].hands.get(index) else {
+            return false;
+        };
+        let early = self.rules.surrender == Surrender::Early && self.surrender_check(hand).is_ok();
+
+        self.insurance_offered(hand) || early
+    }
+
+    // Moves the offers on to the next box that has one, starting from the
+    // given hand of the given seat
+    fn offer_from(&mut self, from: usize, index: usize) {
+        self.phase = Phase::Insurance;
+        for seat in from..self.players.len() {
+            let first = if seat == from { index } else { 0 };
+            for index in first..self.players[seat].hands.len() {
+                if self.has_offer(seat, index) {
+                    self.seat = seat;
+                    self.players[seat].current = index;
+                    return;
+                }
             }
-            Err(e) => {
-                println!("Errors occured: {:?}", e)
+        }
+
+        for player in self.players.iter_mut() {
+            player.current = 0;
+        }
+        // Nobody gets to act on a hand that's already lost
+        if self.dealer_peek() {
+            self.phase = Phase::DealerTurn;
+            self.seat = 0;
+            return;
+        }
+
+        self.phase = Phase::PlayerTurn;
+        for seat in 0..self.players.len() {
+            self.seat = seat;
+            self.next_hand();
+        }
+        self.seat = 0;
+        self.next_seat();
+    }
+
+    // Passes play along to the first seat that still has a hand to finish
+    fn next_seat(&mut self) {
+        while self.seat < self.players.len() && self.players[self.seat].done() {
+            self.seat += 1;
+        }
+
+        if self.seat == self.players.len() {
+            self.phase = Phase::DealerTurn;
+            self.seat = 0;
+        }
+    }
+
+    fn after_move(&mut self) {
+        self.phase = Phase::PlayerTurn;
+        self.next_seat();
+    }
+
+    pub fn seat(&mut self, player: &'a mut Player) -> Result<usize, Errs> {
+        if self.players.len() >= MAX_SEATS {
+            return Err(Errs::TableFull);
+        }
+
+        self.players.push(player);
+        Ok(self.players.len() - 1)
+    }
+
+    // Players can only get up between rounds, and someone has to stay
+    pub fn leave(&mut self, seat: usize) -> Result<(), Errs> {
+        if seat >= self.players.len() {
+            Err(Errs::NoSuchSeat(seat))
+        } else if self.phase != Phase::Betting || self.players.len() == 1 {
+            Err(Errs::CannotLeave)
+        } else {
+            let player = self.players.remove(seat);
+            player.bank += player.bets.drain(..).sum::<i32>();
+            self.seat = 0;
+            Ok(())
+        }
+    }
+
+    pub fn seats(&self) -> usize {
+        self.players.len()
+    }
+
+    pub fn current_seat(&self) -> usize {
+        self.seat
+    }
+
+    pub fn player(&self, seat: usize) -> Option<&Player> {
+        self.players.get(seat).map(|player| &**player)
+    }
+
+    // Picks who's betting. Once the cards are out the game decides whose turn it is.
+    pub fn select_seat(&mut self, seat: usize) -> Result<(), Errs> {
+        if seat >= self.players.len() {
+            Err(Errs::NoSuchSeat(seat))
+        } else if self.phase != Phase::Betting {
+            Err(Errs::OutOfTurn(seat))
+        } else {
+            self.seat = seat;
+            Ok(())
+        }
+    }
+
+    pub fn table_done(&self) -> bool {
+        self.players.iter().all(|player| player.done())
+    }
+
+    pub fn time_played(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn rules(&self) -> &TableRules {
+        &self.rules
+    }
+
+    pub fn shuffle(&mut self) {
+        self.shoe.shuffle_with(&mut self.rng);
+        self.log.push(Event::Shuffled);
+    }
+
+    pub fn events(&self) -> &[Event] {
+        &self.log.events
+    }
+
+    pub fn take_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.log.events)
+    }
+
+    // Every round played so far, oldest first
+    pub fn history(&self) -> &[Round] {
+        &self.history
+    }
+
+    // Sets the table up to play a recorded round again: the round's cards go
+    // on top of a fresh shoe and the same bets go down. The players need to
+    // be sitting where the round's seats were, with the banks they had.
+    pub fn restage(&mut self, round: &Round) -> Result<(), Errs> {
+        self.shoe = Shoe::new(self.rules.num_decks, self.rules.penetration);
+        self.shoe.cards.extend(round.cards().into_iter().rev());
+
+        for (seat, entry) in round.seats.iter().enumerate() {
+            self.select_seat(seat)?;
+            for bet in &entry.bets {
+                self.place_bet(*bet)?;
             }
         }
+        self.select_seat(0)
     }
 
-    pub fn player_hit(&mut self) -> (bool, bool) {
-        match self.player.hit(&mut self.deck.cards) {
-            Ok(card) => {
-                self.player.hand.push(card);
-                match self.player_checks() {
-                    Ok(results) => results,
-                    Err(e) => {
-                        println!("Error Occured: {:?}", e);
-                        (false, false)
-                    }
-                }
+    // Does again whatever was done at one of a recorded round's events, for
+    // a game restaged from that round. Events that the game has already
+    // produced itself, like the cards that come with a hit, are skipped.
+    // Returns whether anything happened.
+    pub fn replay(&mut self, round: &Round, index: usize) -> Result<bool, Errs> {
+        // Shuffles come from the shoe rather than anyone's move, so they're
+        // left out when lining the two rounds up
+        let moves = |events: &[Event]| {
+            events
+                .iter()
+                .filter(|event| **event != Event::Shuffled)
+                .count()
+        };
+        let Some(event) = round.events.get(index) else {
+            return Ok(false);
+        };
+        let done = self.log.round.as_ref().map_or(0, |own| moves(&own.events));
+        if *event == Event::Shuffled || moves(&round.events[..index]) < done {
+            return Ok(false);
+        }
+
+        let turn = |game: &Self, seat: usize, hand: usize| {
+            if game.seat == seat && game.current_hand() == hand {
+                Ok(())
+            } else {
+                Err(Errs::OutOfTurn(seat))
             }
+        };
 
-            Err(e) => {
-                println!("Error Occured: {:?}", e);
-                (false, false)
+        match *event {
+            Event::Shuffled => {}
+            Event::PlayerCard { .. } | Event::HoleCard => self.deal()?,
+            Event::DealerCard(_) if self.phase == Phase::Betting => self.deal()?,
+            Event::DealerCard(_) => self.dealer_hit().map(drop)?,
+            Event::HoleCardRevealed(_) => self.dealer_reveal(),
+            Event::Hit { seat, hand } => {
+                turn(self, seat, hand)?;
+                self.player_hit().map(drop)?
+            }
+            Event::Stood { seat, hand } => {
+                turn(self, seat, hand)?;
+                self.player_stand().map(drop)?
+            }
+            Event::Split { seat, hand } => {
+                turn(self, seat, hand)?;
+                self.player_split()?
+            }
+            Event::Doubled { seat, hand } => {
+                turn(self, seat, hand)?;
+                self.player_double().map(drop)?
+            }
+            Event::Surrendered { seat, hand, .. } => {
+                turn(self, seat, hand)?;
+                self.player_surrender().map(drop)?
+            }
+            Event::InsurancePlaced { seat, hand, amount } => {
+                turn(self, seat, hand)?;
+                self.place_insurance(amount)?
+            }
+            Event::InsuranceDeclined { seat, hand } => {
+                turn(self, seat, hand)?;
+                self.decline_insurance()?
+            }
+            Event::EvenMoney { seat, hand, .. } => {
+                turn(self, seat, hand)?;
+                self.take_even_money().map(drop)?
             }
+            Event::InsuranceSettled { .. } => drop(self.settle_insurance()),
+            Event::Settled(_) => self.settle().map(drop)?,
         }
+        Ok(true)
     }
 
-    pub fn dealer_hit(&mut self) -> (bool, bool) {
-        match self.dealer.hit(&mut self.deck.cards) {
-            Ok(card) => {
-                self.dealer.hand.push(card);
-                match self.dealer_checks() {
-                    Ok(results) => results,
-                    Err(e) => {
-                        println!("Error Occured: {:?}", e);
-                        (false, false)
+    pub fn needs_reshuffle(&self) -> bool {
+        self.shoe.needs_reshuffle()
+    }
+
+    // Only runs out mid-round with a very deep cut card; bring the discards
+    // back in so the round can finish
+    fn ensure_cards(&mut self, count: usize) {
+        if self.shoe.cards_left() < count {
+            self.shuffle();
+        }
+    }
+
+    pub fn place_bet(&mut self, amount: i32) -> Result<(), Errs> {
+        self.check(Action::Bet)?;
+
+        if self.seated().bets.len() >= self.rules.max_spots {
+            Err(Errs::TooManySpots(self.rules.max_spots))
+        } else if amount <= 0 {
+            Err(Errs::InvalidBet(amount))
+        } else if amount < self.rules.min_bet || amount > self.rules.max_bet {
+            Err(Errs::OutsideLimits {
+                bet: amount,
+                min: self.rules.min_bet,
+                max: self.rules.max_bet,
+            })
+        } else if self.get_bank() < amount {
+            Err(Errs::InsufficientFunds {
+                bank: self.get_bank(),
+                bet: amount,
+            })
+        } else {
+            self.remove_bank(amount);
+            Ok(())
+        }
+    }
+
+    pub fn deal(&mut self) -> Result<(), Errs> {
+        self.check(Action::Deal)?;
+        self.log.events.clear();
+        self.log.round = Some(Round {
+            number: self.history.len() + 1,
+            rules: self.rules,
+            seats: self
+                .players
+                .iter()
+                .map(|player| Seat {
+                    name: player.name.clone(),
+                    bank: player.bank + player.bets.iter().sum::<i32>(),
+                    bets: player.bets.clone(),
+                })
+                .collect(),
+            events: Vec::new(),
+        });
+        if self.shoe.needs_reshuffle() {
+            self.shuffle();
+        }
+        let boxes: usize = self.players.iter().map(|player| player.spots()).sum();
+        let needed = 2 + 2 * boxes;
+        self.ensure_cards(needed);
+        if self.shoe.cards_left() < needed {
+            return Err(Errs::DeckExhausted);
+        }
+
+        // Casino order: a card to every box, the dealer's upcard, a second
+        // card to every box, then the hole card
+        for round in 0..2 {
+            for seat in 0..self.players.len() {
+                let player = &mut *self.players[seat];
+                for spot in 0..player.spots() {
+                    let card = player.hit(&mut self.shoe.cards)?;
+                    if round == 0 {
+                        let wager = player.bets.get(spot).copied().unwrap_or(0);
+                        let mut hand = Hand::new(Vec::new(), wager);
+                        hand.spot = spot;
+                        player.hands.push(hand);
                     }
+                    player.hands[spot].cards.push(card);
+                    self.log.push(Event::PlayerCard {
+                        seat,
+                        hand: spot,
+                        card,
+                    });
                 }
             }
 
-            Err(e) => {
-                println!("Error Occured: {:?}", e);
-                (false, false)
+            if round == 0 {
+                let card = self.dealer.hit(&mut self.shoe.cards)?;
+                self.dealer.hand.push(card);
+                self.log.push(Event::DealerCard(card));
+            } else if self.rules.hole_card == HoleCard::Peek {
+                let mut card = self.dealer.hit(&mut self.shoe.cards)?;
+                card.hidden = true;
+                self.dealer.hidden = card;
+                self.log.push(Event::HoleCard);
             }
         }
+
+        for seat in 0..self.players.len() {
+            self.seat = seat;
+            self.players[seat].current = 0;
+            for index in 0..self.players[seat].hands.len() {
+                self.finish_if_done(index);
+            }
+        }
+        self.begin_play();
+
+        Ok(())
     }
 
-    pub fn dealer_reveal(&mut self) {
-        self.dealer.show();
+    fn seated(&self) -> &Player {
+        self.players[self.seat]
     }
 
-    pub fn print(&mut self) {
-        println!("Bank: ${}", self.get_bank());
-        println!("\n\n");
-        print_cards_side_by_side(&self.dealer.hand, Some(self.dealer.hidden));
-        println!("\n\n");
-        print_cards_side_by_side(&self.player.hand, None);
+    fn seated_mut(&mut self) -> &mut Player {
+        self.players[self.seat]
     }
 
-    pub fn clear(&mut self) {
-        self.dealer.hand.clear();
-        self.player.hand.clear();
+    // Split aces that are waiting to be split again don't take cards
+    pub fn can_hit(&self) -> Result<(), Errs> {
+        self.check(Action::Hit)?;
+        let player = self.seated();
+        match player.hands.get(player.current) {
+            Some(hand) if hand.split_aces => Err(Errs::CannotHit),
+            Some(_) => Ok(()),
+            None => Err(Errs::NoHandToPlay),
+        }
     }
 
-    pub fn new_deck(&mut self, num_decks: u32) {
-        self.deck = Deck::new(num_decks);
-        self.shuffle();
+    pub fn player_hit(&mut self) -> Result<HandStatus, Errs> {
+        self.can_hit()?;
+        let index = self.seated().current;
+
+        self.ensure_cards(1);
+        let player = &mut *self.players[self.seat];
+        let card = player.hit(&mut self.shoe.cards)?;
+        player.hands[index].cards.push(card);
+        self.log.push(Event::Hit {
+            seat: self.seat,
+            hand: index,
+        });
+        self.log.push(Event::PlayerCard {
+            seat: self.seat,
+            hand: index,
+            card,
+        });
+
+        let status = self.hand_status(index);
+        self.finish_if_done(index);
+        self.after_move();
+        status
     }
 
-    pub fn ask_insurance(&mut self) -> bool {
-        if self.dealer.hand[0].value == Value::Ace {
-            loop {
-                print!("Do you wnt insurance? (y/n): ");
-                stdout().flush().unwrap();
+    pub fn dealer_hit(&mut self) -> Result<HandStatus, Errs> {
+        self.check(Action::DealerHit)?;
+        self.ensure_cards(1);
+        let card = self.dealer.hit(&mut self.shoe.cards)?;
+        self.dealer.hand.push(card);
+        self.log.push(Event::DealerCard(card));
+        self.dealer_status()
+    }
 
-                let mut inp = String::new();
+    pub fn can_split(&self) -> Result<(), Errs> {
+        self.check(Action::Split)?;
+        let player = self.seated();
+        let hand = match player.hands.get(player.current) {
+            Some(hand) => hand,
+            None => return Err(Errs::CannotSplit),
+        };
 
-                stdin().read_line(&mut inp).unwrap();
+        let pair = hand.value().is_ok_and(|value| value.is_pair);
+        let aces = pair && hand.cards[0].value == Value::Ace;
+
+        if !pair
+            || self.box_full(hand.spot)
+            || (aces && hand.split_aces && !self.rules.resplit_aces)
+        {
+            Err(Errs::CannotSplit)
+        } else if player.bank < hand.wager {
+            Err(Errs::InsufficientFunds {
+                bank: player.bank,
+                bet: hand.wager,
+            })
+        } else {
+            Ok(())
+        }
+    }
 
-                match inp.trim() {
-                    "y" | "Y" => {
-                        self.side_pot = (self.pot as f32 / 2.0).floor() as i32;
-                        self.remove_insurance();
-                        return true;
-                    }
-                    "n" | "N" => return false,
+    // Splits the current pair into two hands with the same wager and deals
+    // each of them a second card. Split aces only ever get that one card.
+    pub fn player_split(&mut self) -> Result<(), Errs> {
+        self.can_split()?;
+        self.ensure_cards(2);
+        if self.shoe.cards_left() < 2 {
+            return Err(Errs::DeckExhausted);
+        }
 
-                    _ => {
-                        println!("That is not an input!");
-                    }
-                }
-            }
+        let seat = self.seat;
+        let player = &mut *self.players[seat];
+        let index = player.current;
+        let hand = &mut player.hands[index];
+        let card = hand.cards.pop().ok_or(Errs::CannotSplit)?;
+        let aces = card.value == Value::Ace;
+        let wager = hand.wager;
+        hand.split = true;
+        hand.split_aces = aces;
+
+        let mut new_hand = Hand::new(vec![card], wager);
+        new_hand.spot = hand.spot;
+        new_hand.split = true;
+        new_hand.split_aces = aces;
+
+        player.bank -= wager;
+        player.stats.splits += 1;
+        player.hands.insert(index + 1, new_hand);
+        self.log.push(Event::Split { seat, hand: index });
+
+        for i in [index, index + 1] {
+            let card = player.hit(&mut self.shoe.cards)?;
+            player.hands[i].cards.push(card);
+            self.log.push(Event::PlayerCard {
+                seat,
+                hand: i,
+                card,
+            });
         }
 
-        false
+        self.finish_if_done(index + 1);
+        self.finish_if_done(index);
+        self.after_move();
+        Ok(())
     }
 
-    pub fn get_bank(&mut self) -> i32 {
-        self.player.bank
+    pub fn can_double(&self) -> Result<(), Errs> {
+        self.check(Action::Double)?;
+        let player = self.seated();
+        let hand = match player.hands.get(player.current) {
+            Some(hand) => hand,
+            None => return Err(Errs::CannotDouble),
+        };
+
+        let total = hand.value()?.total;
+
+        if hand.cards.len() != 2
+            || hand.split_aces
+            || (hand.split && !self.rules.double_after_split)
+            || !self.rules.double.allows(total)
+        {
+            Err(Errs::CannotDouble)
+        } else if player.bank < hand.wager {
+            Err(Errs::InsufficientFunds {
+                bank: player.bank,
+                bet: hand.wager,
+            })
+        } else {
+            Ok(())
+        }
     }
 
-    pub fn add_bank(&mut self, bj: bool) {
-        if bj {
-            self.player.bank += (self.pot as f32 * 2.5).floor() as i32;
+    // Doubles the wager on the current hand, deals it exactly one more card
+    // and stands
+    pub fn player_double(&mut self) -> Result<HandStatus, Errs> {
+        self.can_double()?;
+        self.ensure_cards(1);
+
+        let seat = self.seat;
+        let player = &mut *self.players[seat];
+        let index = player.current;
+        let card = player.hit(&mut self.shoe.cards)?;
+        let hand = &mut player.hands[index];
+
+        player.bank -= hand.wager;
+        player.stats.doubles += 1;
+        hand.wager *= 2;
+        hand.doubled = true;
+        hand.cards.push(card);
+        hand.finished = true;
+        self.log.push(Event::Doubled { seat, hand: index });
+        self.log.push(Event::PlayerCard {
+            seat,
+            hand: index,
+            card,
+        });
+
+        let status = self.hand_status(index);
+        self.next_hand();
+        self.after_move();
+        status
+    }
+
+    fn surrender_check(&self, hand: &Hand) -> Result<(), Errs> {
+        if self.rules.surrender == Surrender::None
+            || hand.cards.len() != 2
+            || hand.split
+            || hand.finished
+        {
+            Err(Errs::CannotSurrender)
         } else {
-            self.player.bank += self.pot * 2;
+            Ok(())
         }
     }
 
-    pub fn reset_bank(&mut self) {
-        self.pot = 0;
-        self.side_pot = 0;
+    pub fn can_surrender(&self) -> Result<(), Errs> {
+        self.check(Action::Surrender)?;
+        let player = self.seated();
+        match player.hands.get(player.current) {
+            Some(hand) => self.surrender_check(hand),
+            None => Err(Errs::CannotSurrender),
+        }
     }
 
-    pub fn revert_bank(&mut self, i: bool) {
-        if !i {
-            self.player.bank += self.pot;
+    // Gives up the hand and hands back half of its wager
+    pub fn player_surrender(&mut self) -> Result<i32, Errs> {
+        self.can_surrender()?;
+
+        let seat = self.seat;
+        let player = self.seated_mut();
+        let index = player.current;
+        let hand = &mut player.hands[index];
+        let refund = hand.wager / 2;
+        hand.surrendered = true;
+        hand.finished = true;
+        player.bank += refund;
+        self.log.push(Event::Surrendered {
+            seat,
+            hand: index,
+            refund,
+        });
+
+        if self.phase == Phase::Insurance {
+            self.offer_from(seat, index + 1);
         } else {
-            self.player.bank += self.pot + self.side_pot;
+            self.next_hand();
+            self.after_move();
         }
+        Ok(refund)
     }
 
-    pub fn remove_bank(&mut self, value: i32) {
-        self.player.bank -= value;
+    // Marks a hand as finished once it can't take any more cards, then moves
+    // play on to the next unfinished hand. Split aces only get their one
+    // card, and stay open if it's another ace that can be split again.
+    fn finish_if_done(&mut self, index: usize) {
+        let player = self.seated();
+        let hand = &player.hands[index];
+        let resplit = self.rules.resplit_aces
+            && hand.value().is_ok_and(|value| value.is_pair)
+            && !self.box_full(hand.spot);
+        let done = (hand.split_aces && !resplit)
+            || !matches!(self.hand_status(index), Ok(HandStatus::Live(_)));
+
+        if done {
+            self.seated_mut().hands[index].finished = true;
+        }
+        self.next_hand();
+    }
+
+    // The split limit is per box, so splits on one box leave the others be
+    fn box_full(&self, spot: usize) -> bool {
+        let hands = self.seated().hands.iter().filter(|hand| hand.spot == spot);
+        hands.count() >= self.rules.max_split_hands
+    }
 
-        if self.player.bank < 0 {
-            self.player.bank = 0;
+    fn next_hand(&mut self) {
+        let player = self.seated_mut();
+        while player.current < player.hands.len() && player.hands[player.current].finished {
+            player.current += 1;
         }
+    }
 
-        self.pot = value;
+    pub fn current_hand(&self) -> usize {
+        self.seated().current
     }
 
-    pub fn remove_insurance(&mut self) {
-        self.player.bank -= self.side_pot;
+    pub fn hand_count(&self) -> usize {
+        self.seated().hands.len()
     }
 
-    pub fn cards_left(&mut self) -> usize {
-        self.deck.cards.len()
+    pub fn hands(&self) -> &[Hand] {
+        &self.seated().hands
     }
 
-    pub fn wait_for_seconds(&mut self, seconds: u64) {
-        print!("\x1B[2J\x1B[1;1H");
-        wait_for_seconds(seconds);
+    // Used when settling so the bank methods act on each hand in turn
+    pub fn select_hand(&mut self, index: usize) {
+        self.seated_mut().current = index;
     }
 
-    pub fn totals(&mut self) -> Result<(i32, i32), Errs> {
-        let player_total = calculate_total(&self.player.hand)?;
+    pub fn player_done(&self) -> bool {
+        self.seated().done()
+    }
 
-        let mut dh = self.dealer.hand.clone();
-        dh.push(self.dealer.hidden);
+    pub fn player_has_blackjack(&self) -> bool {
+        self.hand_status(self.current_hand()) == Ok(HandStatus::Blackjack)
+    }
 
-        let dealer_total = calculate_total(&dh)?;
-        Ok((player_total, dealer_total))
+    // What basic strategy says to do with the current hand. While insurance
+    // is on offer that's either surrendering early or declining, since
+    // basic strategy never insures.
+    pub fn hint(&self) -> Option<Action> {
+        self.hint_from(&Strategy::new(self.rules))
     }
 
-    pub fn player_checks(&mut self) -> Result<(bool, bool), Errs> {
-        if self.player.hand.len() == 5 {
-            return Ok((true, false));
+    // Same as hint, for callers asking often enough to keep a strategy around
+    pub fn hint_from(&self, strategy: &Strategy) -> Option<Action> {
+        let player = self.seated();
+        let hand = player.hands.get(player.current)?;
+        let upcard = self.dealer_upcard()?.value;
+        let legal = self.legal_actions();
+
+        if self.phase == Phase::Insurance {
+            let best = strategy.best(hand, upcard, false, &Action::ALL);
+            if best == Some(Action::Surrender) && legal.contains(&Action::Surrender) {
+                return best;
+            } else if legal.contains(&Action::Decline) {
+                return Some(Action::Decline);
+            }
         }
 
-        match calculate_total(&self.player.hand) {
-            Ok(total) => {
-                if total < 21 {
-                    Ok((false, false))
-                } else if total > 21 {
-                    Ok((false, true))
-                } else {
-                    Ok((true, false))
-                }
+        let peeked = self.rules.hole_card == HoleCard::Peek && self.phase != Phase::Insurance;
+        strategy.best(hand, upcard, peeked, &legal)
+    }
+
+    pub fn dealer_reveal(&mut self) {
+        self.draw_hole_card();
+        if self.dealer.hidden.hidden {
+            self.dealer.show();
+            self.log.push(Event::HoleCardRevealed(self.dealer.hidden));
+        }
+    }
+
+    // Without a peek the dealer only takes a second card once the players are done
+    fn draw_hole_card(&mut self) {
+        if self.dealer.hidden.value == Value::Nil {
+            self.ensure_cards(1);
+            if let Ok(card) = self.dealer.hit(&mut self.shoe.cards) {
+                self.dealer.hidden = card;
+                self.dealer.hidden.hidden = true;
             }
-            Err(e) => Err(e),
         }
     }
 
-    pub fn dealer_checks(&mut self) -> Result<(bool, bool), Errs> {
-        if self.has_blackjack() {
-            return Ok((true, true));
+    // The dealer's cards as they sit on the table, hole card second
+    pub fn dealer_hand(&self) -> Vec<Card> {
+        let mut cards = self.dealer.hand.clone();
+        if self.dealer.hidden.value != Value::Nil {
+            cards.insert(1.min(cards.len()), self.dealer.hidden);
         }
+        cards
+    }
 
-        loop {
-            let mut hand = self.dealer.hand.clone();
-            hand.push(self.dealer.hidden);
+    pub fn dealer_upcard(&self) -> Option<Card> {
+        self.dealer.hand.first().copied()
+    }
 
-            let total = calculate_total(&hand)?;
+    // The dealer looks under an ace or ten-value upcard before anyone acts, so
+    // a dealer blackjack ends the round before more money goes on the table
+    pub fn dealer_peek(&self) -> bool {
+        let peeks = match self.dealer_upcard() {
+            Some(card) => matches!(
+                card.value,
+                Value::Ace | Value::Ten | Value::Jack | Value::Queen | Value::King
+            ),
+            None => false,
+        };
 
-            if total < 17 {
-                wait_for_seconds(1);
+        self.rules.hole_card == HoleCard::Peek && peeks && self.has_blackjack()
+    }
 
-                match self.dealer.hit(&mut self.deck.cards) {
-                    Ok(card) => self.dealer.hand.push(card),
-                    Err(e) => return Err(e),
-                }
-            } else {
-                if total > 21 {
-                    // Busted
-                    return Ok((false, true));
-                } else if total == 21 {
-                    // Got 21 (NOT A BLACKJACK)!!!
-                    return Ok((true, false));
-                } else {
-                    // Resume Play
-                    return Ok((false, false));
-                }
+    pub fn clear(&mut self) -> Result<(), Errs> {
+        self.check(Action::Clear)?;
+        // Bets that were never dealt to go back to their players
+        if self.phase == Phase::Betting {
+            for player in self.players.iter_mut() {
+                player.bank += player.bets.iter().sum::<i32>();
             }
-            print!("\x1B[2J\x1B[1;1H");
-            self.print();
         }
+
+        let hidden = std::mem::replace(
+            &mut self.dealer.hidden,
+            Card::new(Value::Nil, Suit::Nil, true),
+        );
+        self.shoe.discard(self.dealer.hand.drain(..));
+        self.shoe.discard([hidden]);
+        for player in self.players.iter_mut() {
+            for hand in player.hands.drain(..) {
+                self.shoe.discard(hand.cards);
+            }
+            player.current = 0;
+            player.bets.clear();
+        }
+        self.seat = 0;
+        self.phase = Phase::Betting;
+        if let Some(round) = self.log.round.take() {
+            self.history.push(round);
+        }
+        Ok(())
+    }
+
+    pub fn new_deck(&mut self, num_decks: u32) {
+        self.rules.num_decks = num_decks;
+        self.shoe = Shoe::new(num_decks, self.rules.penetration);
+        self.shuffle();
+    }
+
+    // Insurance is offered against an ace on a fresh, unsplit hand
+    fn insurance_offered(&self, hand: &Hand) -> bool {
+        let fresh = hand.cards.len() == 2 && !hand.split && !hand.surrendered && !hand.even_money;
+
+        self.rules.insurance
+            && fresh
+            && hand.insurance == 0
+            && self
+                .dealer_upcard()
+                .is_some_and(|card| card.value == Value::Ace)
     }
 
-    pub fn has_blackjack(&mut self) -> bool {
-        let mut hand = self.dealer.hand.clone();
-        hand.push(self.dealer.hidden);
-        if calculate_total(&hand) == Ok(21) && hand.len() == 2 {
-            true
+    pub fn can_insure(&self) -> bool {
+        let player = self.seated();
+        self.allows(Action::Insurance)
+            && player
+                .hands
+                .get(player.current)
+                .is_some_and(|hand| self.insurance_offered(hand))
+    }
+
+    pub fn max_insurance(&self) -> i32 {
+        self.wager() / 2
+    }
+
+    // A side bet of up to half the wager that the dealer has blackjack
+    pub fn place_insurance(&mut self, amount: i32) -> Result<(), Errs> {
+        self.check(Action::Insurance)?;
+        let max = self.max_insurance();
+        let bank = self.get_bank();
+
+        if !self.can_insure() {
+            Err(Errs::CannotInsure)
+        } else if amount <= 0 || amount > max {
+            Err(Errs::InvalidInsurance { amount, max })
+        } else if bank < amount {
+            Err(Errs::InsufficientFunds { bank, bet: amount })
         } else {
-            false
+            let seat = self.seat;
+            let player = self.seated_mut();
+            let index = player.current;
+            player.hands[index].insurance = amount;
+            player.bank -= amount;
+            self.log.push(Event::InsurancePlaced {
+                seat,
+                hand: index,
+                amount,
+            });
+            self.offer_from(seat, index + 1);
+            Ok(())
         }
     }
 
-    pub fn player_stand(&mut self) {
-        match self.player.stand(&self.player.hand.clone()) {
-            Ok(total) => {
-                println!("Your total is {}", total);
+    pub fn decline_insurance(&mut self) -> Result<(), Errs> {
+        self.check(Action::Decline)?;
+        self.log.push(Event::InsuranceDeclined {
+            seat: self.seat,
+            hand: self.current_hand(),
+        });
+        self.offer_from(self.seat, self.current_hand() + 1);
+        Ok(())
+    }
+
+    // Insurance on the current box
+    pub fn insurance(&self) -> i32 {
+        let player = self.seated();
+        player
+            .hands
+            .get(player.current)
+            .map_or(0, |hand| hand.insurance)
+    }
+
+    // Pays insurance 2:1 if the dealer has blackjack and otherwise takes it.
+    // Returns the net result for each seat that was insured, all its boxes
+    // together, and nothing while there's no hole card to settle against yet
+    pub fn settle_insurance(&mut self) -> Vec<(usize, i32)> {
+        if self.dealer.hidden.value == Value::Nil {
+            return Vec::new();
+        }
+
+        let dealer_blackjack = self.has_blackjack();
+        let mut results = Vec::new();
+        for (seat, player) in self.players.iter_mut().enumerate() {
+            let mut total = None;
+            for (index, hand) in player.hands.iter_mut().enumerate() {
+                let amount = std::mem::take(&mut hand.insurance);
+                if amount == 0 {
+                    continue;
+                }
+
+                let net = if dealer_blackjack {
+                    player.bank += amount * 3;
+                    amount * 2
+                } else {
+                    -amount
+                };
+                self.log.push(Event::InsuranceSettled {
+                    seat,
+                    hand: index,
+                    net,
+                });
+                *total.get_or_insert(0) += net;
             }
 
-            Err(e) => {
-                println!("An Error Occured: {:?}", e);
+            if let Some(net) = total {
+                results.push((seat, net));
             }
         }
+        results
+    }
+
+    pub fn can_take_even_money(&self) -> bool {
+        self.can_insure() && self.player_has_blackjack()
+    }
+
+    // Same as insuring a blackjack for half the bet: paid 1:1 straight away
+    // whatever the dealer turns over
+    pub fn take_even_money(&mut self) -> Result<i32, Errs> {
+        self.check(Action::EvenMoney)?;
+        if !self.can_take_even_money() {
+            return Err(Errs::CannotInsure);
+        }
+
+        let seat = self.seat;
+        let player = self.seated_mut();
+        let index = player.current;
+        let hand = &mut player.hands[index];
+        hand.even_money = true;
+        hand.finished = true;
+        let paid = hand.wager;
+        player.bank += paid * 2;
+        self.log.push(Event::EvenMoney {
+            seat,
+            hand: index,
+            paid,
+        });
+        self.offer_from(seat, index + 1);
+        Ok(paid)
+    }
+
+    pub fn get_bank(&self) -> i32 {
+        self.seated().bank
     }
 
-    pub fn dealer_stand(&mut self) {
-        self.dealer.hand.push(self.dealer.hidden);
-        match self.player.stand(&self.dealer.hand.clone()) {
-            Ok(total) => {
-                println!("{}", total);
+    fn wager(&self) -> i32 {
+        let player = self.seated();
+        let last = player.hands.len().saturating_sub(1);
+        match player.hands.get(player.current.min(last)) {
+            Some(hand) => hand.wager,
+            None => player.bets.last().copied().unwrap_or(0),
+        }
+    }
+
+    pub fn reset_bank(&mut self) {
+        for player in self.players.iter_mut() {
+            player.bets.clear();
+            for hand in player.hands.iter_mut() {
+                hand.insurance = 0;
             }
+        }
+    }
+
+    pub fn remove_bank(&mut self, value: i32) {
+        let player = self.seated_mut();
+        player.bank -= value;
+
+        if player.bank < 0 {
+            player.bank = 0;
+        }
+
+        match player.hands.get_mut(player.current) {
+            Some(hand) => hand.wager = value,
+            None => player.bets.push(value),
+        }
+    }
+
+    pub fn cards_left(&self) -> usize {
+        self.shoe.cards_left()
+    }
+
+    // Everything seen since the last shuffle. The hole card isn't counted
+    // until it's turned over.
+    pub fn running_count(&self, system: &dyn CountingSystem) -> f32 {
+        self.shoe.running_count(system) - system.tag(self.hole_card_unseen())
+    }
+
+    // Decks left to be seen, hole card included
+    pub fn decks_remaining(&self) -> f32 {
+        let hole = (self.hole_card_unseen() != Value::Nil) as usize;
+        (self.shoe.cards_left() + hole) as f32 / 52.0
+    }
+
+    pub fn true_count(&self, system: &dyn CountingSystem) -> f32 {
+        Cards::true_count(self.running_count(system), self.decks_remaining())
+    }
+
+    fn hole_card_unseen(&self) -> Value {
+        if self.dealer.hidden.hidden {
+            self.dealer.hidden.value
+        } else {
+            Value::Nil
+        }
+    }
+
+    pub fn totals(&mut self) -> Result<(i32, i32), Errs> {
+        let player_total = match self.seated().hands.get(self.seated().current) {
+            Some(hand) => hand.value()?.total,
+            None => 0,
+        };
+
+        Ok((player_total, self.dealer.value()?.total))
+    }
+
+    pub fn player_checks(&mut self) -> Result<HandStatus, Errs> {
+        let last = self.hand_count().saturating_sub(1);
+        self.hand_status(self.current_hand().min(last))
+    }
+
+    pub fn hand_status(&self, index: usize) -> Result<HandStatus, Errs> {
+        let hand = self.seated().hands.get(index).ok_or(Errs::NoHandToPlay)?;
+        self.status_of(hand)
+    }
+
+    fn status_of(&self, hand: &Hand) -> Result<HandStatus, Errs> {
+        let value = hand.value()?;
+
+        Ok(if value.is_bust {
+            HandStatus::Bust
+        } else if value.is_blackjack {
+            HandStatus::Blackjack
+        } else if self.rules.five_card_charlie && hand.cards.len() >= 5 {
+            HandStatus::Charlie
+        } else if value.total == 21 {
+            HandStatus::TwentyOne
+        } else {
+            HandStatus::Live(value.total)
+        })
+    }
+
+    pub fn hand_value(&self, index: usize) -> Result<HandValue, Errs> {
+        self.seated()
+            .hands
+            .get(index)
+            .ok_or(Errs::NoHandToPlay)?
+            .value()
+    }
+
+    // Counts the hole card even while it's face down, so it's for the engine
+    // and not for showing to the player
+    pub fn dealer_value(&self) -> Result<HandValue, Errs> {
+        self.dealer.value()
+    }
+
+    pub fn dealer_status(&self) -> Result<HandStatus, Errs> {
+        let value = self.dealer.value()?;
+
+        Ok(if value.is_bust {
+            HandStatus::Bust
+        } else if value.is_blackjack {
+            HandStatus::Blackjack
+        } else if value.total == 21 {
+            HandStatus::TwentyOne
+        } else {
+            HandStatus::Live(value.total)
+        })
+    }
+
+    // The dealer hits below 17, and on soft 17 if the table says so
+    pub fn dealer_should_hit(&self) -> Result<bool, Errs> {
+        let value = self.dealer.value()?;
+        let soft_17 = value.total == 17 && value.is_soft;
+
+        Ok(value.total < 17 || (soft_17 && self.rules.dealer_hits_soft_17))
+    }
+
+    // Whether any hand at the table still has to be beaten by the dealer
+    pub fn dealer_waiting(&self) -> bool {
+        self.players
+            .iter()
+            .flat_map(|player| &player.hands)
+            .any(|hand| {
+                !hand.surrendered
+                    && !hand.even_money
+                    && matches!(self.status_of(hand), Ok(HandStatus::Live(_)))
+            })
+    }
+
+    // Plays out the dealer's hand. Frontends that want to show each card as
+    // it's drawn can call dealer_hit themselves while dealer_should_hit holds.
+    pub fn dealer_checks(&mut self) -> Result<HandStatus, Errs> {
+        self.check(Action::DealerHit)?;
+        self.draw_hole_card();
+        if self.has_blackjack() {
+            return Ok(HandStatus::Blackjack);
+        }
+
+        while self.dealer_should_hit()? {
+            self.dealer_hit()?;
+        }
+        self.dealer_status()
+    }
+
+    // Pays out every hand at the table against the dealer. The dealer draws
+    // first if any hand is still waiting on them. On this table 21 and
+    // five-card charlie win outright unless the dealer has blackjack.
+    pub fn settle(&mut self) -> Result<Vec<Settlement>, Errs> {
+        if self.phase == Phase::Settled {
+            return Err(Errs::AlreadySettled);
+        }
+        self.check(Action::Settle)?;
+        self.phase = Phase::DealerTurn;
+
+        self.dealer_reveal();
+        // Insurance still on the table is settled here too, so it's in the
+        // round's result whether or not the frontend settled it first
+        self.settle_insurance();
+        let dealer_blackjack = self.has_blackjack();
+        let dealer = if self.dealer_waiting() && !dealer_blackjack {
+            self.dealer_checks()?
+        } else {
+            self.dealer_status()?
+        };
+        let dealer_total = self.dealer.value()?.total;
+
+        let mut results = Vec::new();
+        for seat in 0..self.players.len() {
+            for i in 0..self.players[seat].hands.len() {
+                let hand = &self.players[seat].hands[i];
+                let status = self.status_of(hand)?;
+                let wager = hand.wager;
+                let spot = hand.spot;
+
+                let outcome = if hand.surrendered {
+                    RoundOutcome::Surrender
+                } else if hand.even_money {
+                    RoundOutcome::EvenMoney
+                } else {
+                    match status {
+                        HandStatus::Bust => RoundOutcome::Loss,
+                        HandStatus::Blackjack if dealer_blackjack => RoundOutcome::Push,
+                        _ if dealer_blackjack => RoundOutcome::Loss,
+                        HandStatus::Blackjack => RoundOutcome::Blackjack,
+                        HandStatus::TwentyOne | HandStatus::Charlie => RoundOutcome::Win,
+                        HandStatus::Live(_) if dealer == HandStatus::Bust => RoundOutcome::Win,
+                        HandStatus::Live(total) => match total.cmp(&dealer_total) {
+                            std::cmp::Ordering::Greater => RoundOutcome::Win,
+                            std::cmp::Ordering::Equal => RoundOutcome::Push,
+                            std::cmp::Ordering::Less => RoundOutcome::Loss,
+                        },
+                    }
+                };
 
-            Err(e) => {
-                println!("An Error Occured: {:?}", e);
+                // Surrender and even money were already paid when they were taken
+                let (returned, net) = match outcome {
+                    RoundOutcome::Blackjack => {
+                        let win = self.rules.blackjack_payout.pay(wager);
+                        (wager + win, win)
+                    }
+                    RoundOutcome::Win => (wager * 2, wager),
+                    RoundOutcome::Push => (wager, 0),
+                    RoundOutcome::Loss => (0, -wager),
+                    RoundOutcome::Surrender => (0, wager / 2 - wager),
+                    RoundOutcome::EvenMoney => (0, wager),
+                };
+                self.players[seat].bank += returned;
+
+                let settlement = Settlement {
+                    seat,
+                    spot,
+                    hand: i,
+                    status,
+                    outcome,
+                    wager,
+                    net,
+                };
+                self.log.push(Event::Settled(settlement));
+                self.players[seat].stats.record_hand(&settlement);
+                results.push(settlement);
             }
         }
+
+        // A round's result is how far the bank moved from before the bets,
+        // which takes in insurance too. Hands that were on the table before
+        // the game started only have their settlements to go on.
+        let lap = std::mem::replace(&mut self.lap, Instant::now()).elapsed();
+        for seat in 0..self.players.len() {
+            self.players[seat].stats.time += lap;
+            let start = self
+                .log
+                .round
+                .as_ref()
+                .and_then(|round| round.seats.get(seat));
+            let net = match start {
+                Some(start) => (self.players[seat].bank - start.bank) as i64,
+                None => results
+                    .iter()
+                    .filter(|result| result.seat == seat)
+                    .map(|result| result.net as i64)
+                    .sum(),
+            };
+            self.players[seat].stats.record_round(net);
+        }
+
+        self.phase = Phase::Settled;
+        Ok(results)
+    }
+
+    pub fn has_blackjack(&self) -> bool {
+        self.dealer.value().is_ok_and(|value| value.is_blackjack)
+    }
+
+    pub fn player_stand(&mut self) -> Result<HandStatus, Errs> {
+        self.check(Action::Stand)?;
+        let index = self.current_hand();
+        if index >= self.hand_count() {
+            return Err(Errs::NoHandToPlay);
+        }
+
+        let status = self.hand_status(index)?;
+        self.seated_mut().hands[index].finished = true;
+        self.log.push(Event::Stood {
+            seat: self.seat,
+            hand: index,
+        });
+        self.next_hand();
+        self.after_move();
+        Ok(status)
     }
 }
 
@@ -311,8 +1335,21 @@ pub struct Dealer {
 }
 
 impl Dealer {
+    // The hand plus the hole card once there is one
+    fn cards(&self) -> Vec<Card> {
+        let mut hand = self.hand.clone();
+        if self.hidden.value != Value::Nil {
+            hand.push(self.hidden);
+        }
+        hand
+    }
+
+    pub fn value(&self) -> Result<HandValue, Errs> {
+        HandValue::new(&self.cards())
+    }
+
     pub fn show(&mut self) {
-        let old = self.hidden.clone();
+        let old = self.hidden;
         self.hidden = Card {
             value: old.value,
             suit: old.suit,
@@ -330,7 +1367,7 @@ impl User for Dealer {
     }
 
     fn from(hand: Vec<Card>, hidden: Option<Card>, bank: Option<i32>) -> Result<Self, Errs> {
-        if let Some(_) = bank {
+        if bank.is_some() {
             Err(Errs::BankProvided)
         } else if let Some(h) = hidden {
             Ok(Self { hand, hidden: h })
@@ -340,37 +1377,26 @@ impl User for Dealer {
     }
 
     fn deal(&mut self, deck: &mut Vec<Card>) -> Result<[Card; 2], Errs> {
-        let mut hand = Vec::with_capacity(2);
-
-        for i in 0..2 {
-            let card = deck.pop().expect("Failed to pop card of the deck");
-
-            let new_card = Card {
-                value: card.value,
-                suit: card.suit,
-                hidden: i != 0,
-            };
-
-            hand.push(new_card);
+        if deck.len() < 2 {
+            return Err(Errs::DeckExhausted);
         }
 
-        if let [c1, c2] = hand[..] {
-            Ok([c1, c2])
-        } else {
-            Err(Errs::UnknownError)
-        }
+        let upcard = self.hit(deck)?;
+        let mut hole_card = self.hit(deck)?;
+        hole_card.hidden = true;
+        Ok([upcard, hole_card])
     }
 
     fn hit(&mut self, deck: &mut Vec<Card>) -> Result<Card, Errs> {
         match deck.pop() {
             Some(card) => Ok(card),
-            None => Err(Errs::UnknownError),
+            None => Err(Errs::DeckExhausted),
         }
     }
 
-    fn stand(&mut self, hand: &Vec<Card>) -> Result<u32, Errs> {
-        match calculate_total(hand) {
-            Ok(total) => Ok(total as u32),
+    fn stand(&mut self, hand: &[Card]) -> Result<u32, Errs> {
+        match HandValue::new(hand) {
+            Ok(value) => Ok(value.total as u32),
 
             Err(e) => Err(e),
         }
@@ -378,169 +1404,86 @@ impl User for Dealer {
 }
 
 pub struct Player {
+    pub name: String,
     pub bank: i32,
-    pub hand: Vec<Card>,
+    pub hands: Vec<Hand>,
+    pub stats: Stats,
+    current: usize,
+    // One bet per box, placed before the deal
+    bets: Vec<i32>,
+}
+
+impl Player {
+    pub fn done(&self) -> bool {
+        self.hands.iter().all(|hand| hand.finished)
+    }
+
+    pub fn bets(&self) -> &[i32] {
+        &self.bets
+    }
+
+    // Boxes in play this round. Anyone sitting at the table without a bet
+    // still gets dealt the one box.
+    pub fn spots(&self) -> usize {
+        match self.hands.last() {
+            Some(hand) => hand.spot + 1,
+            None => self.bets.len().max(1),
+        }
+    }
+
+    pub fn current_hand(&self) -> usize {
+        self.current
+    }
 }
 
 impl User for Player {
     fn new() -> Self {
         Self {
+            name: "Player".to_string(),
             bank: 1000,
-            hand: Vec::new(),
+            hands: Vec::new(),
+            stats: Stats::default(),
+            current: 0,
+            bets: Vec::new(),
         }
     }
 
     fn from(hand: Vec<Card>, hidden: Option<Card>, bank: Option<i32>) -> Result<Self, Errs> {
-        if let Some(_) = hidden {
+        if hidden.is_some() {
             Err(Errs::HiddenProvided)
         } else if let Some(b) = bank {
-            Ok(Self { bank: b, hand })
+            Ok(Self {
+                name: "Player".to_string(),
+                bank: b,
+                hands: vec![Hand::new(hand, 0)],
+                stats: Stats::default(),
+                current: 0,
+                bets: Vec::new(),
+            })
         } else {
             Err(Errs::NoBankProvided)
         }
     }
 
     fn deal(&mut self, deck: &mut Vec<Card>) -> Result<[Card; 2], Errs> {
-        let mut hand: Vec<Card> = Vec::with_capacity(2);
-
-        for _ in 0..2 {
-            let card = deck.pop().expect("Failed to pop card of the deck");
-
-            hand.push(card);
-        }
-
-        if let [c1, c2] = hand[..] {
-            Ok([c1, c2])
-        } else {
-            Err(Errs::UnknownError)
+        if deck.len() < 2 {
+            return Err(Errs::DeckExhausted);
         }
+        Ok([self.hit(deck)?, self.hit(deck)?])
     }
 
     fn hit(&mut self, deck: &mut Vec<Card>) -> Result<Card, Errs> {
         match deck.pop() {
             Some(card) => Ok(card),
-            None => Err(Errs::UnknownError),
+            None => Err(Errs::DeckExhausted),
         }
     }
 
-    fn stand(&mut self, hand: &Vec<Card>) -> Result<u32, Errs> {
-        match calculate_total(hand) {
-            Ok(total) => Ok(total as u32),
+    fn stand(&mut self, hand: &[Card]) -> Result<u32, Errs> {
+        match HandValue::new(hand) {
+            Ok(value) => Ok(value.total as u32),
 
             Err(e) => Err(e),
         }
     }
 }
-
-fn print_cards_side_by_side(hand: &Vec<Card>, hidden: Option<Card>) {
-    let mut cards = hand.clone();
-
-    if hidden != None {
-        cards.insert(1, hidden.expect("Failed to push hidden"));
-    }
-
-    let card_count = cards.len();
-
-    let mut top_line = vec![];
-    let mut line1 = vec![];
-    let mut line2 = vec![];
-    let mut line3 = vec![];
-    let mut line4 = vec![];
-    let mut bottom_line = vec![];
-
-    for i in 0..card_count {
-        if cards[i].hidden == true {
-            // Last card: face-down with centered "HUGOS" and "CARDS"
-            top_line.push("+-------+".to_string());
-            line1.push("|       |".to_string());
-            line2.push("| HUGOS |".to_string());
-            line3.push("| CARDS |".to_string());
-            line4.push("|       |".to_string());
-            bottom_line.push("+-------+".to_string());
-        } else {
-            let label = cards[i].short_label();
-            top_line.push("+-------+".to_string());
-            line1.push(format!("| {:<3}   |", label));
-            line2.push("|       |".to_string());
-            line3.push("|       |".to_string());
-            line4.push(format!("|   {:>3} |", label));
-            bottom_line.push("+-------+".to_string());
-        }
-    }
-
-    // Print all lines joined by spaces
-    println!();
-    println!("{}", top_line.join("  "));
-    println!("{}", line1.join("  "));
-    println!("{}", line2.join("  "));
-    println!("{}", line3.join("  "));
-    println!("{}", line4.join("  "));
-    println!("{}", bottom_line.join("  "));
-
-    cards.pop();
-}
-
-fn calculate_total(hand: &Vec<Card>) -> Result<i32, Errs> {
-    let mut total = 0;
-    for card in hand {
-        match card.value {
-            Value::Ace => {
-                total += 11;
-            }
-
-            Value::Two => {
-                total += 2;
-            }
-
-            Value::Three => {
-                total += 3;
-            }
-
-            Value::Four => {
-                total += 4;
-            }
-
-            Value::Five => {
-                total += 5;
-            }
-
-            Value::Six => {
-                total += 6;
-            }
-
-            Value::Seven => {
-                total += 7;
-            }
-
-            Value::Eight => {
-                total += 8;
-            }
-
-            Value::Nine => {
-                total += 9;
-            }
-
-            Value::Ten | Value::Jack | Value::King | Value::Queen => {
-                total += 10;
-            }
-
-            Value::Nil => {
-                return Err(Errs::UnknownError);
-            }
-        }
-    }
-
-    if total > 21 {
-        for card in hand.iter() {
-            if card.value == Value::Ace && total > 21 {
-                total -= 10;
-            }
-        }
-    }
-
-    Ok(total)
-}
-
-fn wait_for_seconds(seconds: u64) {
-    std::thread::sleep(std::time::Duration::from_secs(seconds));
-}
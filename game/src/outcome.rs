@@ -0,0 +1,40 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HandStatus {
+    Blackjack,
+    TwentyOne,
+    Charlie,
+    Bust,
+    Live(i32),
+}
+
+impl HandStatus {
+    // Anything but a live hand is finished drawing cards
+    pub fn is_done(&self) -> bool {
+        !matches!(self, HandStatus::Live(_))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RoundOutcome {
+    Blackjack,
+    Win,
+    Push,
+    Loss,
+    Surrender,
+    EvenMoney,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Settlement {
+    pub seat: usize,
+    pub spot: usize,
+    pub hand: usize,
+    pub status: HandStatus,
+    pub outcome: RoundOutcome,
+    pub wager: i32,
+    // What the hand won or lost overall, so a push is 0 and a lost $10 bet is -10
+    pub net: i32,
+}
@@ -0,0 +1,81 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Phase {
+    Betting,
+    // Straight after the deal, while insurance, even money or early
+    // surrender go round the table. Every box answers before the peek.
+    Insurance,
+    PlayerTurn,
+    DealerTurn,
+    Settled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Bet,
+    Deal,
+    Hit,
+    Stand,
+    Double,
+    Split,
+    Surrender,
+    Insurance,
+    EvenMoney,
+    Decline,
+    DealerHit,
+    Settle,
+    Clear,
+}
+
+impl Action {
+    pub const ALL: [Action; 13] = [
+        Action::Bet,
+        Action::Deal,
+        Action::Hit,
+        Action::Stand,
+        Action::Double,
+        Action::Split,
+        Action::Surrender,
+        Action::Insurance,
+        Action::EvenMoney,
+        Action::Decline,
+        Action::DealerHit,
+        Action::Settle,
+        Action::Clear,
+    ];
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Phase::Betting => "betting",
+            Phase::Insurance => "insurance offer",
+            Phase::PlayerTurn => "player's turn",
+            Phase::DealerTurn => "dealer's turn",
+            Phase::Settled => "end of the round",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Action::Bet => "bet",
+            Action::Deal => "deal",
+            Action::Hit => "hit",
+            Action::Stand => "stand",
+            Action::Double => "double",
+            Action::Split => "split",
+            Action::Surrender => "surrender",
+            Action::Insurance => "insurance",
+            Action::EvenMoney => "even money",
+            Action::Decline => "decline",
+            Action::DealerHit => "dealer hit",
+            Action::Settle => "settle",
+            Action::Clear => "clear",
+        };
+        write!(f, "{}", name)
+    }
+}
@@ -0,0 +1,104 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Payout {
+    ThreeToTwo,
+    SixToFive,
+    EvenMoney,
+}
+
+impl Payout {
+    // Winnings on a blackjack, not counting the original bet
+    pub fn pay(&self, bet: i32) -> i32 {
+        match self {
+            Payout::ThreeToTwo => bet * 3 / 2,
+            Payout::SixToFive => bet * 6 / 5,
+            Payout::EvenMoney => bet,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Surrender {
+    None,
+    Late,
+    Early,
+}
+
+// Peek: the dealer checks the hole card for blackjack before anyone acts.
+// European: no hole card until the players are done, and a dealer blackjack
+// then takes every bet on the table, doubles and splits included.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HoleCard {
+    Peek,
+    European,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Double {
+    AnyTwo,
+    NineToEleven,
+}
+
+impl Double {
+    pub fn allows(&self, total: i32) -> bool {
+        match self {
+            Double::AnyTwo => true,
+            Double::NineToEleven => (9..=11).contains(&total),
+        }
+    }
+}
+
+// Saved rules from before a field existed pick up its default
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct TableRules {
+    pub num_decks: u32,
+    pub penetration: f32,
+    pub dealer_hits_soft_17: bool,
+    pub hole_card: HoleCard,
+    pub blackjack_payout: Payout,
+    pub five_card_charlie: bool,
+    pub insurance: bool,
+    pub surrender: Surrender,
+    pub double: Double,
+    pub double_after_split: bool,
+    pub max_split_hands: usize,
+    pub resplit_aces: bool,
+    // How many boxes one player can bet on in a round
+    pub max_spots: usize,
+    // Table limits for each box
+    pub min_bet: i32,
+    pub max_bet: i32,
+}
+
+impl TableRules {
+    pub fn new(num_decks: u32) -> Self {
+        Self {
+            num_decks,
+            penetration: 0.75,
+            dealer_hits_soft_17: false,
+            hole_card: HoleCard::Peek,
+            blackjack_payout: Payout::ThreeToTwo,
+            five_card_charlie: true,
+            insurance: true,
+            surrender: Surrender::None,
+            double: Double::AnyTwo,
+            double_after_split: true,
+            max_split_hands: 4,
+            resplit_aces: false,
+            max_spots: 3,
+            min_bet: 1,
+            max_bet: i32::MAX,
+        }
+    }
+}
+
+impl Default for TableRules {
+    fn default() -> Self {
+        Self::new(2)
+    }
+}
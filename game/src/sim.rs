@@ -0,0 +1,215 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+
+use crate::{
+    Action, Dealer, Errs, Game, HandStatus, Phase, Player, RoundOutcome, Settlement, Strategy,
+    TableRules, User,
+};
+
+// Every round is a flat bet of this much, so it divides evenly for every payout
+pub const BET: i32 = 10;
+
+// Rounds are played in batches with their own seed, so the results come out
+// the same however many threads share the work
+const BATCH: u64 = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Play {
+    Basic,
+    Mimic,
+    NeverBust,
+}
+
+pub struct Simulation {
+    pub rounds: u64,
+    pub seed: u64,
+    pub threads: usize,
+    pub play: Play,
+    pub rules: TableRules,
+}
+
+// Moves tracked for EV, by the first decision made on a round
+pub const MOVES: [Action; 5] = [
+    Action::Hit,
+    Action::Stand,
+    Action::Double,
+    Action::Split,
+    Action::Surrender,
+];
+
+pub const OUTCOMES: [RoundOutcome; 6] = [
+    RoundOutcome::Blackjack,
+    RoundOutcome::Win,
+    RoundOutcome::Push,
+    RoundOutcome::Loss,
+    RoundOutcome::Surrender,
+    RoundOutcome::EvenMoney,
+];
+
+// Everything is counted in whole dollars so batches add up exactly
+#[derive(Debug, Default, PartialEq)]
+pub struct Tally {
+    pub rounds: u64,
+    pub net: i64,
+    pub squares: i64,
+    pub hands: u64,
+    pub busts: u64,
+    pub outcomes: [u64; 6],
+    // Rounds and net result for each first move
+    pub moves: [(u64, i64); 5],
+}
+
+impl Tally {
+    fn record(&mut self, results: &[Settlement], first: Option<Action>) {
+        let net: i64 = results.iter().map(|result| result.net as i64).sum();
+        self.rounds += 1;
+        self.net += net;
+        self.squares += net * net;
+
+        for result in results {
+            self.hands += 1;
+            if result.status == HandStatus::Bust {
+                self.busts += 1;
+            }
+            if let Some(i) = OUTCOMES.iter().position(|&o| o == result.outcome) {
+                self.outcomes[i] += 1;
+            }
+        }
+
+        if let Some(i) = first.and_then(|action| MOVES.iter().position(|&m| m == action)) {
+            self.moves[i].0 += 1;
+            self.moves[i].1 += net;
+        }
+    }
+
+    fn add(&mut self, other: &Tally) {
+        self.rounds += other.rounds;
+        self.net += other.net;
+        self.squares += other.squares;
+        self.hands += other.hands;
+        self.busts += other.busts;
+        for (mine, theirs) in self.outcomes.iter_mut().zip(other.outcomes) {
+            *mine += theirs;
+        }
+        for (mine, theirs) in self.moves.iter_mut().zip(other.moves) {
+            mine.0 += theirs.0;
+            mine.1 += theirs.1;
+        }
+    }
+}
+
+pub fn simulate(config: &Simulation) -> Result<Tally, Errs> {
+    let strategy = Strategy::new(config.rules);
+    let batches = config.rounds.div_ceil(BATCH);
+    let next = AtomicU64::new(0);
+
+    let tallies = thread::scope(|scope| {
+        let workers: Vec<_> = (0..config.threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut tally = Tally::default();
+                    loop {
+                        let batch = next.fetch_add(1, Ordering::Relaxed);
+                        if batch >= batches {
+                            return Ok(tally);
+                        }
+
+                        let rounds = BATCH.min(config.rounds - batch * BATCH);
+                        let seed = config.seed ^ (batch + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+                        tally.add(&play_batch(config, &strategy, seed, rounds)?);
+                    }
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .map(|worker| worker.join().expect("a simulation thread panicked"))
+            .collect::<Vec<Result<Tally, Errs>>>()
+    });
+
+    let mut total = Tally::default();
+    for tally in tallies {
+        total.add(&tally?);
+    }
+    Ok(total)
+}
+
+fn play_batch(
+    config: &Simulation,
+    strategy: &Strategy,
+    seed: u64,
+    rounds: u64,
+) -> Result<Tally, Errs> {
+    let mut dealer = Dealer::new();
+    let mut player = Player::new();
+    // Deep enough pockets that the bank never runs dry mid-batch
+    player.bank = i32::MAX / 2;
+    let mut game = Game::with_seed(config.rules, seed, &mut dealer, &mut player);
+    let mut tally = Tally::default();
+
+    for _ in 0..rounds {
+        if game.needs_reshuffle() {
+            game.shuffle();
+        }
+        game.place_bet(BET)?;
+        game.deal()?;
+
+        let mut first = None;
+        loop {
+            let action = match game.phase() {
+                Phase::Insurance => decide(config.play, strategy, &game),
+                Phase::PlayerTurn => {
+                    let action = decide(config.play, strategy, &game);
+                    first.get_or_insert(action);
+                    action
+                }
+                _ => break,
+            };
+
+            match action {
+                Action::Hit => game.player_hit().map(drop)?,
+                Action::Double => game.player_double().map(drop)?,
+                Action::Split => game.player_split()?,
+                Action::Surrender => {
+                    first.get_or_insert(action);
+                    game.player_surrender().map(drop)?
+                }
+                Action::Decline => game.decline_insurance()?,
+                _ => game.player_stand().map(drop)?,
+            }
+        }
+
+        tally.record(&game.settle()?, first);
+        game.reset_bank();
+        game.clear()?;
+    }
+    Ok(tally)
+}
+
+fn decide(play: Play, strategy: &Strategy, game: &Game) -> Action {
+    if play == Play::Basic
+        && let Some(action) = game.hint_from(strategy)
+    {
+        return action;
+    }
+    if game.phase() == Phase::Insurance {
+        return Action::Decline;
+    }
+
+    let value = match game.hand_value(game.current_hand()) {
+        Ok(value) => value,
+        Err(_) => return Action::Stand,
+    };
+    let hit = match play {
+        Play::Mimic => value.total < 17,
+        Play::NeverBust => value.total < 12 || (value.is_soft && value.total < 18),
+        Play::Basic => false,
+    };
+
+    if hit && game.can_hit().is_ok() {
+        Action::Hit
+    } else {
+        Action::Stand
+    }
+}
@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use crate::{HandStatus, RoundOutcome, Settlement};
+
+// How a player has done since they sat down, or over every session when
+// they're saved and loaded again
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Stats {
+    pub hands: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub pushes: u32,
+    pub blackjacks: u32,
+    pub busts: u32,
+    pub doubles: u32,
+    pub splits: u32,
+    pub rounds: u32,
+    pub net: i64,
+    // The best single round
+    pub biggest_win: i64,
+    // The furthest the net result has fallen from its high point
+    pub max_drawdown: i64,
+    // Time at the table, added up a round at a time
+    pub time: Duration,
+    peak: i64,
+}
+
+impl Stats {
+    // Even money counts as a win and a surrender as a loss
+    pub fn record_hand(&mut self, result: &Settlement) {
+        self.hands += 1;
+        match result.outcome {
+            RoundOutcome::Win | RoundOutcome::Blackjack | RoundOutcome::EvenMoney => self.wins += 1,
+            RoundOutcome::Loss | RoundOutcome::Surrender => self.losses += 1,
+            RoundOutcome::Push => self.pushes += 1,
+        }
+        match result.status {
+            HandStatus::Blackjack => self.blackjacks += 1,
+            HandStatus::Bust => self.busts += 1,
+            _ => {}
+        }
+    }
+
+    // Everything a round won or lost, insurance included
+    pub fn record_round(&mut self, net: i64) {
+        self.rounds += 1;
+        self.net += net;
+        self.biggest_win = self.biggest_win.max(net);
+        self.peak = self.peak.max(self.net);
+        self.max_drawdown = self.max_drawdown.max(self.peak - self.net);
+    }
+}
@@ -0,0 +1,320 @@
+use Cards::Value;
+
+use crate::{Action, Hand, HoleCard, Surrender, TableRules};
+
+// The chance of drawing each rank from an infinite shoe, aces first and the
+// four ten-value ranks together at the end
+const RANKS: [(i32, f64); 10] = [
+    (1, 1.0 / 13.0),
+    (2, 1.0 / 13.0),
+    (3, 1.0 / 13.0),
+    (4, 1.0 / 13.0),
+    (5, 1.0 / 13.0),
+    (6, 1.0 / 13.0),
+    (7, 1.0 / 13.0),
+    (8, 1.0 / 13.0),
+    (9, 1.0 / 13.0),
+    (10, 4.0 / 13.0),
+];
+
+// Player hands are looked up by total, softness and card count. Card counts
+// stop at five since that's as far as five-card charlie cares.
+const STATES: usize = 22 * 2 * 6;
+
+fn state(total: i32, soft: bool, cards: usize) -> usize {
+    (total as usize * 2 + soft as usize) * 6 + cards.min(5)
+}
+
+fn rank(value: Value) -> Option<i32> {
+    match value {
+        Value::Ace => Some(1),
+        Value::Two => Some(2),
+        Value::Three => Some(3),
+        Value::Four => Some(4),
+        Value::Five => Some(5),
+        Value::Six => Some(6),
+        Value::Seven => Some(7),
+        Value::Eight => Some(8),
+        Value::Nine => Some(9),
+        Value::Ten | Value::Jack | Value::Queen | Value::King => Some(10),
+        Value::Nil => None,
+    }
+}
+
+// Adds a card to a total, counting an ace as 11 while it fits
+fn add(total: i32, soft: bool, card: i32) -> (i32, bool) {
+    let (mut total, mut soft) = (total + card, soft);
+    if card == 1 && !soft && total + 10 <= 21 {
+        total += 10;
+        soft = true;
+    }
+    if total > 21 && soft {
+        total -= 10;
+        soft = false;
+    }
+    (total, soft)
+}
+
+// Expected values against one dealer upcard, assuming the dealer doesn't
+// have blackjack
+struct Upcard {
+    blackjack: f64,
+    // Where the dealer finishes: 17 to 21, then bust
+    finish: [f64; 6],
+    // Hitting and then carrying on the best way, for every hand
+    hit: Vec<f64>,
+    // Splitting a pair of each rank, both hands together
+    split: [f64; 10],
+}
+
+// Basic strategy worked out from the expected value of every move for the
+// table's rules. The shoe is treated as infinite, which is what basic
+// strategy charts assume, and resplits aren't counted.
+pub struct Strategy {
+    rules: TableRules,
+    upcards: Vec<Upcard>,
+}
+
+impl Strategy {
+    pub fn new(rules: TableRules) -> Self {
+        let mut strategy = Self {
+            rules,
+            upcards: Vec::new(),
+        };
+
+        for (upcard, _) in RANKS {
+            let (blackjack, finish) = strategy.dealer(upcard);
+            let mut table = Upcard {
+                blackjack,
+                finish,
+                hit: Vec::new(),
+                split: [0.0; 10],
+            };
+
+            let mut memo = vec![None; STATES];
+            let mut hit = vec![0.0; STATES];
+            for total in 2..=21 {
+                for soft in [false, true] {
+                    for cards in 1..=5 {
+                        let i = state(total, soft, cards);
+                        hit[i] = strategy.hit(&table, &mut memo, total, soft, cards);
+                    }
+                }
+            }
+            table.hit = hit;
+
+            for (pair, _) in RANKS {
+                table.split[pair as usize - 1] = strategy.split(&table, pair);
+            }
+            strategy.upcards.push(table);
+        }
+        strategy
+    }
+
+    // The chance of a dealer blackjack under this upcard, and where the
+    // dealer finishes when they don't have one
+    fn dealer(&self, upcard: i32) -> (f64, [f64; 6]) {
+        let mut memo = vec![None; 22 * 2];
+        let (total, soft) = add(0, false, upcard);
+        let mut blackjack = 0.0;
+        let mut finish = [0.0; 6];
+
+        for (card, chance) in RANKS {
+            let (total, soft) = add(total, soft, card);
+            if total == 21 {
+                blackjack += chance;
+                continue;
+            }
+            let next = self.dealer_from(&mut memo, total, soft);
+            for (sum, p) in finish.iter_mut().zip(next) {
+                *sum += chance * p;
+            }
+        }
+
+        for p in finish.iter_mut() {
+            *p /= 1.0 - blackjack;
+        }
+        (blackjack, finish)
+    }
+
+    fn dealer_from(&self, memo: &mut Vec<Option<[f64; 6]>>, total: i32, soft: bool) -> [f64; 6] {
+        let mut finish = [0.0; 6];
+        if total > 21 {
+            finish[5] = 1.0;
+            return finish;
+        }
+        let soft_17 = total == 17 && soft && self.rules.dealer_hits_soft_17;
+        if total >= 17 && !soft_17 {
+            finish[total as usize - 17] = 1.0;
+            return finish;
+        }
+
+        let key = total as usize * 2 + soft as usize;
+        if let Some(finish) = memo[key] {
+            return finish;
+        }
+        for (card, chance) in RANKS {
+            let (total, soft) = add(total, soft, card);
+            let next = self.dealer_from(memo, total, soft);
+            for (sum, p) in finish.iter_mut().zip(next) {
+                *sum += chance * p;
+            }
+        }
+        memo[key] = Some(finish);
+        finish
+    }
+
+    // On this table 21 and five-card charlie win outright
+    fn stand(&self, table: &Upcard, total: i32, cards: usize) -> f64 {
+        if total == 21 || (self.rules.five_card_charlie && cards >= 5) {
+            return 1.0;
+        }
+
+        let mut ev = table.finish[5];
+        for (dealer, p) in (17..=21).zip(table.finish) {
+            ev += p * (total - dealer).signum() as f64;
+        }
+        ev
+    }
+
+    fn hit(
+        &self,
+        table: &Upcard,
+        memo: &mut Vec<Option<f64>>,
+        total: i32,
+        soft: bool,
+        cards: usize,
+    ) -> f64 {
+        let i = state(total, soft, cards);
+        if let Some(ev) = memo[i] {
+            return ev;
+        }
+
+        let mut ev = 0.0;
+        for (card, chance) in RANKS {
+            let (total, soft) = add(total, soft, card);
+            let cards = cards + 1;
+            let next = if total > 21 {
+                -1.0
+            } else if total == 21 || (self.rules.five_card_charlie && cards >= 5) {
+                1.0
+            } else {
+                let stand = self.stand(table, total, cards);
+                stand.max(self.hit(table, memo, total, soft, cards))
+            };
+            ev += chance * next;
+        }
+        memo[i] = Some(ev);
+        ev
+    }
+
+    fn double(&self, table: &Upcard, total: i32, soft: bool, cards: usize) -> f64 {
+        let mut ev = 0.0;
+        for (card, chance) in RANKS {
+            let (total, _) = add(total, soft, card);
+            ev += chance
+                * if total > 21 {
+                    -1.0
+                } else {
+                    self.stand(table, total, cards + 1)
+                };
+        }
+        2.0 * ev
+    }
+
+    fn split(&self, table: &Upcard, pair: i32) -> f64 {
+        let (first, soft) = add(0, false, pair);
+        let mut ev = 0.0;
+
+        for (card, chance) in RANKS {
+            let (total, soft) = add(first, soft, card);
+            // Split aces get one card, and two cards to 21 is a 21 rather
+            // than a blackjack
+            let hand = if pair == 1 || total == 21 {
+                self.stand(table, total, 2)
+            } else {
+                let mut best = self
+                    .stand(table, total, 2)
+                    .max(table.hit[state(total, soft, 2)]);
+                if self.rules.double_after_split && self.rules.double.allows(total) {
+                    best = best.max(self.double(table, total, soft, 2));
+                }
+                best
+            };
+            ev += chance * hand;
+        }
+        2.0 * ev
+    }
+
+    // The expected value of every move the rules allow on this hand, per unit
+    // bet. Unless the dealer has already checked for blackjack, the chance of
+    // one is taken off: the whole stake goes without a hole card, only the
+    // original bet with one.
+    pub fn evaluate(&self, hand: &Hand, upcard: Value, peeked: bool) -> Vec<(Action, f64)> {
+        let (Ok(value), Some(up)) = (hand.value(), rank(upcard)) else {
+            return Vec::new();
+        };
+        let cards = hand.cards.len();
+        let charlie = self.rules.five_card_charlie && cards >= 5;
+        if value.is_bust || value.total >= 21 || charlie || hand.finished {
+            return Vec::new();
+        }
+
+        let table = &self.upcards[up as usize - 1];
+        let (total, soft) = (value.total, value.is_soft);
+        let mut moves = vec![
+            (Action::Stand, self.stand(table, total, cards)),
+            (Action::Hit, table.hit[state(total, soft, cards)]),
+        ];
+
+        if cards == 2
+            && !hand.split_aces
+            && (!hand.split || self.rules.double_after_split)
+            && self.rules.double.allows(total)
+        {
+            moves.push((Action::Double, self.double(table, total, soft, cards)));
+        }
+        if value.is_pair
+            && let Some(pair) = rank(hand.cards[0].value)
+        {
+            moves.push((Action::Split, table.split[pair as usize - 1]));
+        }
+
+        let european = self.rules.hole_card == HoleCard::European;
+        if !peeked || european {
+            for (action, ev) in moves.iter_mut() {
+                let stake = match action {
+                    Action::Double | Action::Split if european => 2.0,
+                    _ => 1.0,
+                };
+                *ev = table.blackjack * -stake + (1.0 - table.blackjack) * *ev;
+            }
+        }
+
+        if self.rules.surrender != Surrender::None && cards == 2 && !hand.split {
+            moves.push((Action::Surrender, -0.5));
+        }
+        moves
+    }
+
+    // The best of the moves given, or None if none of them apply to the hand
+    pub fn best(
+        &self,
+        hand: &Hand,
+        upcard: Value,
+        peeked: bool,
+        allowed: &[Action],
+    ) -> Option<Action> {
+        self.evaluate(hand, upcard, peeked)
+            .into_iter()
+            .filter(|(action, _)| allowed.contains(action))
+            .fold(
+                None,
+                |best: Option<(Action, f64)>, (action, ev)| match best {
+                    Some((_, top)) if top >= ev => best,
+                    _ => Some((action, ev)),
+                },
+            )
+            .map(|(action, _)| action)
+    }
+}
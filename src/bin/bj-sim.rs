@@ -0,0 +1,185 @@
+use std::env;
+use std::process;
+use std::thread;
+use std::time::Instant;
+
+use ::Game::{
+    BET, Double, HoleCard, MOVES, OUTCOMES, Payout, Play, Simulation, Surrender, TableRules, Tally,
+    simulate,
+};
+
+const USAGE: &str = "Usage: bj-sim [options]
+
+  --rounds N           rounds to play (default 1000000)
+  --seed N             seed for the shoe (default random)
+  --threads N          worker threads (default all cores)
+  --strategy NAME      basic, mimic (play like the dealer) or never-bust
+  --decks N            decks in the shoe (default 6)
+  --penetration F      how far into the shoe the cut card goes (default 0.75)
+  --h17                dealer hits soft 17
+  --payout P           blackjack pays 3:2, 6:5 or 1:1
+  --european           no hole card
+  --surrender S        none, late or early
+  --double D           any or 9-11
+  --no-das             no doubling after a split
+  --no-charlie         no five-card charlie
+  --max-hands N        most hands a split can make (default 4)
+  --resplit-aces       aces can be split again
+  --no-insurance       insurance isn't offered";
+
+fn main() {
+    let config = match parse(env::args().skip(1).collect()) {
+        Ok(config) => config,
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let start = Instant::now();
+    match simulate(&config) {
+        Ok(tally) => report(&config, &tally, start.elapsed().as_secs_f64()),
+        Err(e) => {
+            eprintln!("The simulation stopped: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn parse(args: Vec<String>) -> Result<Simulation, String> {
+    let mut config = Simulation {
+        rounds: 1_000_000,
+        seed: rand::random(),
+        threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        play: Play::Basic,
+        rules: TableRules::new(6),
+    };
+
+    let mut args = args.into_iter();
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} needs a value", flag));
+
+        match flag.as_str() {
+            "--rounds" => config.rounds = number(&flag, &value()?)?,
+            "--seed" => config.seed = number(&flag, &value()?)?,
+            "--threads" => config.threads = number(&flag, &value()?)?,
+            "--strategy" => {
+                config.play = match value()?.as_str() {
+                    "basic" => Play::Basic,
+                    "mimic" => Play::Mimic,
+                    "never-bust" => Play::NeverBust,
+                    other => return Err(format!("unknown strategy '{}'", other)),
+                }
+            }
+            "--decks" => config.rules.num_decks = number(&flag, &value()?)?,
+            "--penetration" => config.rules.penetration = number(&flag, &value()?)?,
+            "--h17" => config.rules.dealer_hits_soft_17 = true,
+            "--payout" => {
+                config.rules.blackjack_payout = match value()?.as_str() {
+                    "3:2" => Payout::ThreeToTwo,
+                    "6:5" => Payout::SixToFive,
+                    "1:1" => Payout::EvenMoney,
+                    other => return Err(format!("unknown payout '{}'", other)),
+                }
+            }
+            "--european" => config.rules.hole_card = HoleCard::European,
+            "--surrender" => {
+                config.rules.surrender = match value()?.as_str() {
+                    "none" => Surrender::None,
+                    "late" => Surrender::Late,
+                    "early" => Surrender::Early,
+                    other => return Err(format!("unknown surrender rule '{}'", other)),
+                }
+            }
+            "--double" => {
+                config.rules.double = match value()?.as_str() {
+                    "any" => Double::AnyTwo,
+                    "9-11" => Double::NineToEleven,
+                    other => return Err(format!("unknown double rule '{}'", other)),
+                }
+            }
+            "--no-das" => config.rules.double_after_split = false,
+            "--no-charlie" => config.rules.five_card_charlie = false,
+            "--max-hands" => config.rules.max_split_hands = number(&flag, &value()?)?,
+            "--resplit-aces" => config.rules.resplit_aces = true,
+            "--no-insurance" => config.rules.insurance = false,
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            other => return Err(format!("unknown option '{}'", other)),
+        }
+    }
+
+    if config.rounds == 0 || config.threads == 0 || config.rules.num_decks == 0 {
+        return Err("rounds, threads and decks all have to be at least 1".to_string());
+    }
+    if !(0.0..1.0).contains(&config.rules.penetration) {
+        return Err("penetration has to be at least 0 and less than 1".to_string());
+    }
+    Ok(config)
+}
+
+fn number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("'{}' isn't a valid value for {}", value, flag))
+}
+
+fn report(config: &Simulation, tally: &Tally, seconds: f64) {
+    let rounds = tally.rounds as f64;
+    let bet = BET as f64;
+    let mean = tally.net as f64 / rounds / bet;
+    let variance = tally.squares as f64 / rounds / (bet * bet) - mean * mean;
+    let sd = variance.max(0.0).sqrt();
+    let margin = 1.96 * sd / rounds.sqrt();
+
+    println!("Rounds played       {}", tally.rounds);
+    println!("Seed                {}", config.seed);
+    println!(
+        "Time                {:.1}s ({:.0} rounds/s)",
+        seconds,
+        rounds / seconds.max(f64::EPSILON)
+    );
+    println!();
+    println!(
+        "House edge          {:.3}% (± {:.3}% at 95%)",
+        -mean * 100.0,
+        margin * 100.0
+    );
+    println!("Player EV           {:+.4} bets per round", mean);
+    println!("Standard deviation  {:.4} bets per round", sd);
+    println!();
+
+    println!("First move    Rounds     Share     EV/bet");
+    for (action, (count, net)) in MOVES.iter().zip(tally.moves) {
+        if count == 0 {
+            continue;
+        }
+        println!(
+            "{:<12}{:>8}{:>9.2}%{:>+11.4}",
+            action.to_string(),
+            count,
+            count as f64 / rounds * 100.0,
+            net as f64 / count as f64 / bet
+        );
+    }
+    println!();
+
+    let hands = tally.hands as f64;
+    println!("Outcome       Hands      Share");
+    for (outcome, count) in OUTCOMES.iter().zip(tally.outcomes) {
+        println!(
+            "{:<12}{:>8}{:>9.2}%",
+            format!("{:?}", outcome),
+            count,
+            count as f64 / hands * 100.0
+        );
+    }
+    println!(
+        "{:<12}{:>8}{:>9.2}%",
+        "Bust",
+        tally.busts,
+        tally.busts as f64 / hands * 100.0
+    );
+}
@@ -0,0 +1,350 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use ::Cards::{CountingSystem, counting_system};
+use ::Game::{Double, HoleCard, MAX_SEATS, Payout, Surrender, TableRules};
+use serde::Deserialize;
+use toml::{Table, Value};
+
+use crate::save::{self, Save};
+
+pub const USAGE: &str = "Usage: bj [options]
+       bj replay <file>
+
+Options can also be set in ~/.config/bj/config.toml, with the same names
+and no dashes in front, e.g. decks = 6 or payout = \"6:5\". Options given
+on the command line win over the file.
+
+  --config FILE        read this config file instead
+  --profile NAME       profile to load or create
+  --players N          players at a new profile's table, 1-7
+  --name NAME          name of the first player at a new profile's table
+  --bank N             starting bank for new players (default 1000)
+  --seed N             seed for a fresh shoe (default random)
+
+  --decks N            decks in the shoe, 1-8 (default 2)
+  --penetration F      how far into the shoe the cut card goes (default 0.75)
+  --min-bet N          table minimum (default 1)
+  --max-bet N          table maximum (default none)
+  --max-boxes N        boxes one player can bet on (default 3)
+  --h17                dealer hits soft 17 (h17 = true)
+  --payout P           blackjack pays 3:2, 6:5 or 1:1
+  --european           no hole card (hole-card = \"european\")
+  --surrender S        none, late or early
+  --double D           any or 9-11
+  --no-das             no doubling after a split (das = false)
+  --no-charlie         no five-card charlie (charlie = false)
+  --max-hands N        most hands a split can make (default 4)
+  --resplit-aces       aces can be split again (resplit-aces = true)
+  --no-insurance       insurance isn't offered (insurance = false)
+
+  --warn               warn before moves against basic strategy (warn = true)
+  --trainer SYSTEM     practice counting with a system, or 'off'
+  --delay N            seconds between the dealer's cards, 0-10 (default 1)";
+
+// Everything the file or the flags can set. Numbers are read wide so a bad
+// value gets a proper message rather than a type error.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct Config {
+    profile: Option<String>,
+    players: Option<i64>,
+    name: Option<String>,
+    bank: Option<i64>,
+    // Any u64, so it can be a string when it's too big for a TOML integer
+    seed: Option<Value>,
+    decks: Option<i64>,
+    penetration: Option<f64>,
+    min_bet: Option<i64>,
+    max_bet: Option<i64>,
+    max_boxes: Option<i64>,
+    h17: Option<bool>,
+    payout: Option<String>,
+    hole_card: Option<String>,
+    surrender: Option<String>,
+    double: Option<String>,
+    das: Option<bool>,
+    charlie: Option<bool>,
+    max_hands: Option<i64>,
+    resplit_aces: Option<bool>,
+    insurance: Option<bool>,
+    warn: Option<bool>,
+    trainer: Option<String>,
+    delay: Option<i64>,
+}
+
+pub enum Practice {
+    Ask,
+    Off,
+    With(&'static dyn CountingSystem),
+}
+
+pub enum Command {
+    Play(Box<Settings>),
+    Replay(String),
+    Help,
+}
+
+pub struct Settings {
+    pub profile: Option<String>,
+    pub players: Option<usize>,
+    pub name: Option<String>,
+    pub bank: i32,
+    pub seed: Option<u64>,
+    pub warn: bool,
+    pub practice: Practice,
+    pub delay: u64,
+    config: Config,
+}
+
+// Where the config file is looked for when --config isn't given
+pub fn default_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("bj").join("config.toml"))
+}
+
+pub fn from_args(args: Vec<String>, default: Option<PathBuf>) -> Result<Command, String> {
+    if args.first().is_some_and(|arg| arg == "replay") {
+        return match args.as_slice() {
+            [_, path] => Ok(Command::Replay(path.clone())),
+            _ => Err("replay needs the file to play back".to_string()),
+        };
+    }
+
+    let mut flags = Table::new();
+    let mut file = None;
+    let mut args = args.into_iter();
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} needs a value", flag));
+        let key = flag.trim_start_matches("--");
+
+        let setting: Value = match flag.as_str() {
+            "--config" => {
+                file = Some(PathBuf::from(value()?));
+                continue;
+            }
+            "--players" | "--bank" | "--decks" | "--min-bet" | "--max-bet" | "--max-boxes"
+            | "--max-hands" | "--delay" => number::<i64>(&flag, &value()?)?.into(),
+            "--seed" => number::<u64>(&flag, &value()?)?.to_string().into(),
+            "--penetration" => number::<f64>(&flag, &value()?)?.into(),
+            "--profile" | "--name" | "--payout" | "--surrender" | "--double" | "--trainer" => {
+                value()?.into()
+            }
+            "--h17" | "--resplit-aces" | "--warn" => true.into(),
+            "--european" => {
+                flags.insert("hole-card".to_string(), "european".into());
+                continue;
+            }
+            "--no-das" | "--no-charlie" | "--no-insurance" => {
+                flags.insert(key.trim_start_matches("no-").to_string(), false.into());
+                continue;
+            }
+            "--help" | "-h" => return Ok(Command::Help),
+            other => return Err(format!("unknown option '{}'", other)),
+        };
+        flags.insert(key.to_string(), setting);
+    }
+
+    // The file's settings, with the flags on top
+    let mut table = read(file, default)?;
+    table.extend(flags);
+    let config: Config = table
+        .try_into()
+        .map_err(|e: toml::de::Error| e.to_string())?;
+    settings(config).map(|settings| Command::Play(Box::new(settings)))
+}
+
+fn number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("'{}' isn't a valid value for {}", value, flag))
+}
+
+// A missing config file is fine unless it was asked for by name
+fn read(file: Option<PathBuf>, default: Option<PathBuf>) -> Result<Table, String> {
+    let (path, required) = match (file, default) {
+        (Some(path), _) => (path, true),
+        (None, Some(path)) => (path, false),
+        (None, None) => return Ok(Table::new()),
+    };
+
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound && !required => return Ok(Table::new()),
+        Err(e) => return Err(format!("can't read {}: {}", path.display(), e)),
+    };
+    // Checked as a whole first, so mistakes are reported against the file's
+    // own lines
+    let error = |e: toml::de::Error| format!("{}: {}", path.display(), e);
+    toml::from_str::<Config>(&text).map_err(error)?;
+    text.parse::<Table>().map_err(error)
+}
+
+fn within(name: &str, value: Option<i64>, min: i64, max: i64) -> Result<Option<i64>, String> {
+    match value {
+        Some(value) if value < min || value > max => Err(format!(
+            "{} has to be between {} and {}, not {}",
+            name, min, max, value
+        )),
+        _ => Ok(value),
+    }
+}
+
+fn seed(value: &Value) -> Result<u64, String> {
+    let seed = match value {
+        Value::Integer(seed) => u64::try_from(*seed).ok(),
+        Value::String(seed) => seed.parse().ok(),
+        _ => None,
+    };
+    seed.ok_or(format!(
+        "seed has to be a whole number from 0 to {}, not {}",
+        u64::MAX,
+        value
+    ))
+}
+
+fn settings(config: Config) -> Result<Settings, String> {
+    if let Some(profile) = &config.profile
+        && !save::valid_name(profile)
+    {
+        return Err(save::SaveError::BadName(profile.clone()).to_string());
+    }
+    if let Some(name) = &config.name
+        && (name.trim().is_empty() || name.len() > 24)
+    {
+        return Err("name has to be between 1 and 24 characters".to_string());
+    }
+
+    let practice = match config.trainer.as_deref() {
+        None => Practice::Ask,
+        Some("off") | Some("none") => Practice::Off,
+        Some(name) => match counting_system(name) {
+            Some(system) => Practice::With(system),
+            None => return Err(format!("there's no counting system called '{}'", name)),
+        },
+    };
+
+    let settings = Settings {
+        profile: config.profile.clone(),
+        players: within("players", config.players, 1, MAX_SEATS as i64)?.map(|n| n as usize),
+        name: config.name.as_ref().map(|name| name.trim().to_string()),
+        bank: within("bank", config.bank, 1, i32::MAX as i64)?.unwrap_or(1000) as i32,
+        seed: config.seed.as_ref().map(seed).transpose()?,
+        warn: config.warn.unwrap_or(false),
+        practice,
+        delay: within("delay", config.delay, 0, 10)?.unwrap_or(1) as u64,
+        config,
+    };
+
+    // Catches bad rules now rather than once a profile has been picked
+    settings.rules(TableRules::default())?;
+    Ok(settings)
+}
+
+impl Settings {
+    // The rules a profile plays under. A saved profile keeps its own table,
+    // so only the rules can change on top of it.
+    pub fn table(&self, saved: Option<&Save>) -> Result<TableRules, String> {
+        let Some(save) = saved else {
+            return self.rules(TableRules::default());
+        };
+        if self.players.is_some() || self.name.is_some() {
+            return Err(
+                "players and name only set up a new profile, this one already has its table"
+                    .to_string(),
+            );
+        }
+        self.rules(save.rules)
+    }
+
+    // The rules that were set, on top of the table's own
+    pub fn rules(&self, base: TableRules) -> Result<TableRules, String> {
+        let config = &self.config;
+        let mut rules = base;
+
+        if let Some(decks) = within("decks", config.decks, 1, 8)? {
+            rules.num_decks = decks as u32;
+        }
+        if let Some(penetration) = config.penetration {
+            if !(0.0..1.0).contains(&penetration) {
+                return Err("penetration has to be at least 0 and less than 1".to_string());
+            }
+            rules.penetration = penetration as f32;
+        }
+        if let Some(min) = within("min-bet", config.min_bet, 1, i32::MAX as i64)? {
+            rules.min_bet = min as i32;
+        }
+        if let Some(max) = within("max-bet", config.max_bet, 1, i32::MAX as i64)? {
+            rules.max_bet = max as i32;
+        }
+        if let Some(boxes) = within("max-boxes", config.max_boxes, 1, MAX_SEATS as i64)? {
+            rules.max_spots = boxes as usize;
+        }
+        if let Some(hands) = within("max-hands", config.max_hands, 1, 8)? {
+            rules.max_split_hands = hands as usize;
+        }
+
+        if let Some(payout) = &config.payout {
+            rules.blackjack_payout = match payout.as_str() {
+                "3:2" => Payout::ThreeToTwo,
+                "6:5" => Payout::SixToFive,
+                "1:1" => Payout::EvenMoney,
+                other => return Err(format!("unknown payout '{}', use 3:2, 6:5 or 1:1", other)),
+            };
+        }
+        if let Some(hole_card) = &config.hole_card {
+            rules.hole_card = match hole_card.as_str() {
+                "peek" => HoleCard::Peek,
+                "european" => HoleCard::European,
+                other => {
+                    return Err(format!(
+                        "unknown hole card rule '{}', use peek or european",
+                        other
+                    ));
+                }
+            };
+        }
+        if let Some(surrender) = &config.surrender {
+            rules.surrender = match surrender.as_str() {
+                "none" => Surrender::None,
+                "late" => Surrender::Late,
+                "early" => Surrender::Early,
+                other => {
+                    return Err(format!(
+                        "unknown surrender rule '{}', use none, late or early",
+                        other
+                    ));
+                }
+            };
+        }
+        if let Some(double) = &config.double {
+            rules.double = match double.as_str() {
+                "any" => Double::AnyTwo,
+                "9-11" => Double::NineToEleven,
+                other => {
+                    return Err(format!("unknown double rule '{}', use any or 9-11", other));
+                }
+            };
+        }
+
+        rules.dealer_hits_soft_17 = config.h17.unwrap_or(rules.dealer_hits_soft_17);
+        rules.double_after_split = config.das.unwrap_or(rules.double_after_split);
+        rules.five_card_charlie = config.charlie.unwrap_or(rules.five_card_charlie);
+        rules.resplit_aces = config.resplit_aces.unwrap_or(rules.resplit_aces);
+        rules.insurance = config.insurance.unwrap_or(rules.insurance);
+
+        if rules.max_bet < rules.min_bet {
+            return Err(format!(
+                "the maximum bet (${}) is under the minimum (${})",
+                rules.max_bet, rules.min_bet
+            ));
+        }
+        if self.bank < rules.min_bet {
+            return Err(format!(
+                "a ${} bank can't cover the ${} minimum bet",
+                self.bank, rules.min_bet
+            ));
+        }
+        Ok(rules)
+    }
+}
@@ -0,0 +1,5 @@
+pub mod config;
+pub mod render;
+pub mod replay;
+pub mod save;
+pub mod trainer;
@@ -1,215 +1,717 @@
-use std::cmp::Ordering::*;
-use std::io::{stdin, stdout, Write};
+use std::env;
+use std::io::{Write, stdin, stdout};
+use std::process;
 
-use crossterm::event::{self, Event};
-
-use ::Game::{Dealer, Game, Player, User};
+use ::Cards::{SYSTEMS, counting_system};
+use ::Game::{
+    Action, Dealer, Game, HandStatus, HoleCard, MAX_SEATS, Phase, Player, RoundOutcome, Surrender,
+    TableRules, User,
+};
+use bj::config::{self, Command, Practice, Settings};
+use bj::render::{self, wait_for_input, wait_for_seconds};
+use bj::replay;
+use bj::save::{self, Save};
+use bj::trainer::Trainer;
 
 fn main() {
+    let settings = match config::from_args(env::args().skip(1).collect(), config::default_path()) {
+        Ok(Command::Play(settings)) => settings,
+        Ok(Command::Help) => {
+            println!("{}", config::USAGE);
+            return;
+        }
+        Ok(Command::Replay(path)) => {
+            if let Err(e) = replay::run(&path) {
+                eprintln!("Can't replay: {}", e);
+                process::exit(1);
+            }
+            return;
+        }
+        Err(e) => {
+            eprintln!("bj: {}\n\n{}", e, config::USAGE);
+            process::exit(2);
+        }
+    };
+
+    // Anything set on the command line or in the config file goes on top of
+    // the rules the profile was saved with
+    let (profile, saved, rules) = match &settings.profile {
+        Some(profile) => match save::load(profile) {
+            Ok(saved) => match settings.table(saved.as_ref()) {
+                Ok(rules) => (profile.clone(), saved, rules),
+                Err(e) => {
+                    eprintln!("bj: can't play '{}': {}", profile, e);
+                    process::exit(2);
+                }
+            },
+            Err(e) => {
+                eprintln!("bj: can't load '{}': {}", profile, e);
+                process::exit(2);
+            }
+        },
+        None => ask_profile(&settings),
+    };
+    if let Some(save) = &saved {
+        welcome_back(save);
+    }
+
     let mut dealer = Dealer::new();
-    let mut player = Player::new();
+    let mut players: Vec<Player> = match &saved {
+        Some(save) => save.players(settings.bank),
+        None => (0..settings.players.unwrap_or_else(ask_players))
+            .map(|i| {
+                let mut player = Player::new();
+                player.name = match (&settings.name, i) {
+                    (Some(name), 0) => name.clone(),
+                    _ => format!("Player {}", i + 1),
+                };
+                player.bank = settings.bank;
+                player
+            })
+            .collect(),
+    };
+    let mut trainer = match settings.practice {
+        Practice::Ask => ask_trainer(),
+        Practice::Off => None,
+        Practice::With(system) => Some(Trainer::new(system)),
+    };
 
-    let mut game = Game::new(2, &mut dealer, &mut player);
+    let (first, rest) = players.split_first_mut().unwrap();
+    let seed = settings.seed.unwrap_or_else(rand::random);
+    let mut game = Game::with_seed(rules, seed, &mut dealer, first);
+    for player in rest {
+        if let Err(e) = game.seat(player) {
+            println!("Errors Occured: {}", e);
+        }
+    }
+
+    // A seeded game starts from its own shoe so it can be played again
+    let mut fresh = true;
+    let mut save = match saved {
+        Some(mut save) => {
+            if settings.seed.is_none() {
+                fresh = !save.restore_shoe(&mut game);
+            }
+            save.rules = rules;
+            save
+        }
+        None => Save::new(&game),
+    };
+    save.sessions += 1;
+    write_save(&profile, &save);
+
+    // Warn before a move that goes against basic strategy
+    let mut warn = settings.warn;
 
-    game.deal();
     loop {
-        print!("\x1B[2J\x1B[1;1H");
-        if game.cards_left() <= 14 {
-            println!("There are no cards left in the deck");
-            game.new_deck(2);
-        } else if game.get_bank() == 0 {
-            println!("The House wins!");
+        render::clear_screen();
+        if !place_bets(&mut game) {
             break;
         }
-        let out = place_bets(&mut game);
 
-        if out {
-            println!("Goodbye!");
-            return;
+        if let Err(e) = game.deal() {
+            println!("Errors Occured: {}", e);
+            wait_for_input();
+            continue;
+        }
+        render::print(&game);
+        play_round(&mut game, &mut warn, settings.delay);
+        if let Some(trainer) = trainer.as_mut() {
+            trainer.quiz(&game);
         }
 
-        game.print();
-        let has_insurance = game.ask_insurance();
+        game.reset_bank();
+        restart(&mut game);
+        save.update(&game);
+        write_save(&profile, &save);
+    }
 
-        loop {
-            if has_insurance {
-                if game.has_blackjack() {
-                    game.revert_bank(true);
-                    game.dealer_reveal();
-                    game.print();
-                    println!("The dealer had a blackjack!");
-                    wait_for_input();
-                    break;
-                } else {
-                    println!("The dealer didn't have a blackjack :(");
-                    wait_for_input();
+    if !game.history().is_empty() {
+        match save::export_history(&profile, game.history()) {
+            Ok(paths) => {
+                println!("This session's hands were saved to:");
+                for path in paths {
+                    println!("  {}", path.display());
                 }
             }
+            Err(e) => println!("Couldn't save the hand history: {}", e),
+        }
+    }
+
+    if let Some(trainer) = &trainer {
+        trainer.report();
+    }
+    // A shoe carried over from the last session can't be dealt again from
+    // the seed alone
+    if fresh {
+        println!("This session's shoe was shuffled with --seed {}", seed);
+    }
+}
+
+// Picks a profile by name, loading it if it's been saved before. Profiles
+// the options can't be played with are pointed out before anyone picks one.
+fn ask_profile(settings: &Settings) -> (String, Option<Save>, TableRules) {
+    let profiles = save::profiles();
+    if !profiles.is_empty() {
+        println!("Saved profiles: {}", profiles.join(", "));
+    }
+    for profile in &profiles {
+        if let Ok(saved) = save::load(profile)
+            && let Err(e) = settings.table(saved.as_ref())
+        {
+            println!("  '{}' can't be played with these options: {}", profile, e);
+        }
+    }
+
+    loop {
+        print!("Which profile are you playing? Press [ENTER] for 'default': ");
+        stdout().flush().unwrap();
+
+        let mut inp = String::new();
+        stdin().read_line(&mut inp).unwrap();
+
+        let profile = match inp.trim() {
+            "" => "default",
+            name => name,
+        };
+        match save::load(profile) {
+            Ok(saved) => match settings.table(saved.as_ref()) {
+                Ok(rules) => return (profile.to_string(), saved, rules),
+                Err(e) => println!("Can't play '{}': {}", profile, e),
+            },
+            Err(e) => println!("Can't load '{}': {}", profile, e),
+        }
+    }
+}
+
+fn welcome_back(save: &Save) {
+    println!(
+        "Welcome back! {} sessions and {} rounds played so far.",
+        save.sessions, save.rounds
+    );
+    for player in &save.players {
+        println!("{} - Bank: ${}", player.name, player.bank);
+    }
+}
 
-            print!("\x1B[2J\x1B[1;1H");
-            println!("There are {} cards left in the deck.", game.cards_left());
-            game.print();
+fn write_save(profile: &str, save: &Save) {
+    if let Err(e) = save.write(profile) {
+        println!("Couldn't save '{}': {}", profile, e);
+        wait_for_input();
+    }
+}
+
+fn ask_players() -> usize {
+    loop {
+        print!("How many players are sitting down? (1-{}): ", MAX_SEATS);
+        stdout().flush().unwrap();
+
+        let mut inp = String::new();
+        stdin().read_line(&mut inp).unwrap();
+
+        match inp.trim().parse::<usize>() {
+            Ok(count) if (1..=MAX_SEATS).contains(&count) => return count,
+            _ => println!("That is not an input!"),
+        }
+    }
+}
+
+fn ask_trainer() -> Option<Trainer> {
+    let names: Vec<&str> = SYSTEMS.iter().map(|system| system.name()).collect();
+    loop {
+        print!(
+            "Practice counting? Pick a system ({}) or press [ENTER] to just play: ",
+            names.join(", ")
+        );
+        stdout().flush().unwrap();
+
+        let mut inp = String::new();
+        stdin().read_line(&mut inp).unwrap();
+
+        if inp.trim().is_empty() {
+            return None;
+        }
+        match counting_system(inp.trim()) {
+            Some(system) => return Some(Trainer::new(system)),
+            None => println!("That is not an input!"),
+        }
+    }
+}
+
+fn name(game: &Game) -> String {
+    match game.player(game.current_seat()) {
+        Some(player) => player.name.clone(),
+        None => String::new(),
+    }
+}
+
+// The player whose turn it is, with the box they're on if they have several
+fn turn(game: &Game) -> String {
+    let seat = game.current_seat();
+    let label = game
+        .player(seat)
+        .and_then(|player| render::hand_label(player, player.current_hand()));
 
-            if game.player_checks() == Ok((true, false)) {
-                println!("YOU GOT A BLACKJACK!");
-                game.add_bank(true);
+    match label {
+        Some(label) => format!("{} ({})", name(game), label),
+        None => name(game),
+    }
+}
+
+fn play_round(game: &mut Game, warn: &mut bool, delay: u64) {
+    // Insurance, even money and early surrender go round the table first
+    while game.phase() == Phase::Insurance {
+        render::clear_screen();
+        render::print(game);
+        println!("{}:", turn(game));
+
+        if game.rules().surrender == Surrender::Early
+            && game.can_surrender().is_ok()
+            && ask_early_surrender(game, *warn)
+        {
+            continue;
+        }
+
+        if game.can_take_even_money() {
+            if ask_yes_no("You have a blackjack! Do you want even money? (y/n): ")
+                && confirm(game, *warn, Action::EvenMoney)
+                && let Ok(paid) = game.take_even_money()
+            {
+                println!("You took even money and won ${}.", paid);
                 wait_for_input();
-                break;
+                continue;
             }
+        } else if game.can_insure() && ask_insurance(game, *warn) {
+            continue;
+        }
 
-            print!("Enter an input (hit, stand): ");
-            stdout().flush().unwrap();
+        if let Err(e) = game.decline_insurance() {
+            println!("Errors Occured: {}", e);
+            wait_for_input();
+            break;
+        }
+    }
 
-            let mut response = String::new();
-            stdin()
-                .read_line(&mut response)
-                .expect("Failed to read input");
+    if game.dealer_peek() {
+        game.dealer_reveal();
+        render::clear_screen();
+        render::print(game);
+        println!("The dealer had a blackjack!");
+        settle_insurance(game);
+        show_results(game);
+        return;
+    } else if game.rules().hole_card == HoleCard::Peek && !game.settle_insurance().is_empty() {
+        println!("The dealer didn't have a blackjack :(");
+        wait_for_input();
+    }
 
-            match response.trim() {
-                "hit" => {
-                    let (won, bust) = game.player_hit();
+    while game.phase() == Phase::PlayerTurn {
+        render::clear_screen();
+        println!("There are {} cards left in the deck.", game.cards_left());
+        render::print(game);
 
-                    if won || bust {
-                        if won {
-                            print!("\x1B[2J\x1B[1;1H");
-                            game.print();
-                            println!("YOU GOT 21!!!!!!!");
-                            game.add_bank(false);
-                        }
+        print!(
+            "{}, enter an input ({}, hint, warn, stats): ",
+            turn(game),
+            moves(game).join(", ")
+        );
+        stdout().flush().unwrap();
 
-                        if bust {
-                            print!("\x1B[2J\x1B[1;1H");
-                            game.print();
-                            println!("BUSTED!!!!");
-                        }
+        let mut response = String::new();
+        stdin()
+            .read_line(&mut response)
+            .expect("Failed to read input");
 
-                        wait_for_input();
-                        break;
-                    }
+        let response = response.trim();
+        let chosen = match response {
+            "hit" => Some(Action::Hit),
+            "stand" => Some(Action::Stand),
+            "double" => Some(Action::Double),
+            "split" => Some(Action::Split),
+            "surrender" => Some(Action::Surrender),
+            _ => None,
+        };
+        if let Some(action) = chosen
+            && !confirm(game, *warn, action)
+        {
+            continue;
+        }
+
+        match response {
+            "hint" => {
+                match game.hint() {
+                    Some(action) => println!("Basic strategy says: {}", action),
+                    None => println!("Basic strategy has nothing to say here."),
                 }
+                wait_for_input();
+            }
 
-                "stand" => {
-                    stand(&mut game);
-                    break;
+            "stats" => {
+                render::clear_screen();
+                render::print_stats(game);
+                wait_for_input();
+            }
+
+            "warn" => {
+                *warn = !*warn;
+                if *warn {
+                    println!("You'll be warned when you play against basic strategy.");
+                } else {
+                    println!("Basic strategy warnings are off.");
                 }
+                wait_for_input();
+            }
 
-                _ => {
-                    println!("Invalid input! Try 'hit' or 'stand'");
+            "hit" => match game.player_hit() {
+                Ok(HandStatus::Live(_)) => {}
+                Ok(status) => {
+                    render::clear_screen();
+                    render::print(game);
+                    println!("{}", status_message(status));
+                    wait_for_input();
+                }
+                Err(e) => {
+                    println!("Errors Occured: {}", e);
+                    wait_for_input();
+                }
+            },
+
+            "stand" => {
+                if let Err(e) = game.player_stand() {
+                    println!("Errors Occured: {}", e);
+                    wait_for_input();
                 }
             }
+
+            "double" => match game.player_double() {
+                Ok(status) => {
+                    render::clear_screen();
+                    render::print(game);
+                    println!("{}", status_message(status));
+                    wait_for_input();
+                }
+                Err(e) => {
+                    println!("You can't double that: {}", e);
+                    wait_for_input();
+                }
+            },
+
+            "split" => {
+                if let Err(e) = game.player_split() {
+                    println!("You can't split that: {}", e);
+                    wait_for_input();
+                }
+            }
+
+            "surrender" => match game.player_surrender() {
+                Ok(refund) => {
+                    println!("You surrendered and got ${} back.", refund);
+                    wait_for_input();
+                }
+                Err(e) => {
+                    println!("You can't surrender now: {}", e);
+                    wait_for_input();
+                }
+            },
+
+            _ => {
+                println!("Invalid input! Try one of: {}", moves(game).join(", "));
+            }
         }
+    }
 
-        game.reset_bank();
-        restart(&mut game);
+    stand(game, delay);
+}
+
+// The moves the player can make on the current hand
+fn moves(game: &Game) -> Vec<String> {
+    game.legal_actions()
+        .into_iter()
+        .filter(|action| {
+            matches!(
+                action,
+                Action::Hit | Action::Stand | Action::Double | Action::Split | Action::Surrender
+            )
+        })
+        .map(|action| action.to_string())
+        .collect()
+}
+
+// With warnings on, checks with the player before a move basic strategy
+// wouldn't make
+fn confirm(game: &Game, warn: bool, action: Action) -> bool {
+    match game.hint() {
+        Some(hint) if warn && hint != action => {
+            println!("Basic strategy says {} here, not {}.", hint, action);
+            ask_yes_no("Do it anyway? (y/n): ")
+        }
+        _ => true,
     }
 }
 
-fn wait_for_input() {
-    println!("Press [ENTER]! to continue: ");
-    stdout().flush().unwrap();
-    std::thread::sleep(std::time::Duration::from_secs(0));
+fn ask_early_surrender(game: &mut Game, warn: bool) -> bool {
+    if !ask_yes_no("Do you want to surrender before the dealer checks for blackjack? (y/n): ")
+        || !confirm(game, warn, Action::Surrender)
+    {
+        return false;
+    }
+
+    match game.player_surrender() {
+        Ok(refund) => {
+            println!("You surrendered and got ${} back.", refund);
+            wait_for_input();
+            true
+        }
+        Err(_) => false,
+    }
+}
 
+fn ask_yes_no(question: &str) -> bool {
     loop {
-        if event::poll(std::time::Duration::from_millis(10)).unwrap() {
-            if let Event::Key(_) = event::read().unwrap() {
-                break;
+        print!("{}", question);
+        stdout().flush().unwrap();
+
+        let mut inp = String::new();
+        stdin().read_line(&mut inp).unwrap();
+
+        match inp.trim() {
+            "y" | "Y" => return true,
+            "n" | "N" => return false,
+            _ => println!("That is not an input!"),
+        }
+    }
+}
+
+// Returns whether the insurance was placed
+fn ask_insurance(game: &mut Game, warn: bool) -> bool {
+    loop {
+        print!(
+            "Do you want insurance? Enter an amount up to ${} or 'n': ",
+            game.max_insurance()
+        );
+        stdout().flush().unwrap();
+
+        let mut inp = String::new();
+        stdin().read_line(&mut inp).unwrap();
+
+        let inp = inp.trim();
+        if !matches!(inp, "n" | "N" | "0") && !confirm(game, warn, Action::Insurance) {
+            return false;
+        }
+
+        match inp {
+            "n" | "N" | "0" => return false,
+            "y" | "Y" => {
+                let amount = game.max_insurance();
+                match game.place_insurance(amount) {
+                    Ok(()) => return true,
+                    Err(e) => println!("Can't place the insurance: {}", e),
+                }
             }
+            amount => match amount.parse::<i32>() {
+                Ok(amount) => match game.place_insurance(amount) {
+                    Ok(()) => return true,
+                    Err(e) => println!("Can't place the insurance: {}", e),
+                },
+                Err(e) => println!("Errors occured: {}", e),
+            },
+        }
+    }
+}
+
+fn seat_name(game: &Game, seat: usize) -> String {
+    match game.player(seat) {
+        Some(player) if game.seats() > 1 => format!("{}: ", player.name),
+        _ => String::new(),
+    }
+}
+
+fn settle_insurance(game: &mut Game) {
+    for (seat, net) in game.settle_insurance() {
+        if net > 0 {
+            println!("{}Insurance pays ${}!", seat_name(game, seat), net);
+        } else {
+            println!(
+                "{}You lost your ${} insurance.",
+                seat_name(game, seat),
+                -net
+            );
         }
     }
-    print!("\x1B[2J\x1B[1;1H");
 }
 
-fn stand(game: &mut Game) {
-    game.player_stand();
-    game.wait_for_seconds(1);
-    print!("\x1B[2J\x1B[1;1h");
-    game.print();
+fn stand(game: &mut Game, delay: u64) {
+    wait_for_seconds(delay);
     game.dealer_reveal();
-    game.wait_for_seconds(1);
-    game.print();
-    print!("\x1B[2J\x1B[1;1H");
-    game.print();
-
-    let result = game.dealer_checks();
-    if let Ok(vals) = result {
-        match vals {
-            (false, true) => {
-                println!("THEY BUSTED EVERYWHERE!!!!");
-                game.add_bank(false);
-            }
-            (true, false) => {
-                println!("THEY GOT 21!!!!!!!!!!!!!!!");
-            }
-            (false, false) => match game.totals() {
-                Ok((pt, dt)) => {
-                    println!("The results were: YOU: {}, DEALER: {}", pt, dt);
-                    match pt.cmp(&dt) {
-                        Less => println!("YOU LOST :("),
-                        Greater => {
-                            println!("YOU WON! :)");
-                            game.add_bank(false);
-                        }
-                        Equal => {
-                            println!("PUSH!");
-                            game.revert_bank(false);
-                        }
-                    }
-                }
+    render::clear_screen();
+    render::print(game);
+    settle_insurance(game);
+
+    // Only make the dealer draw if there's a hand left for them to beat,
+    // a card at a time so it's easy to follow
+    if game.dealer_waiting() {
+        loop {
+            match game.dealer_should_hit() {
+                Ok(true) => {}
+                Ok(false) => break,
                 Err(e) => {
-                    println!("Errors Occured: {:?}", e);
+                    println!("Errors Occured: {}", e);
+                    break;
                 }
-            },
-            (true, true) => {
-                println!("THEY GOT A BLACKJACK!");
             }
+
+            wait_for_seconds(delay);
+            if let Err(e) = game.dealer_hit() {
+                println!("Errors Occured: {}", e);
+                break;
+            }
+            render::clear_screen();
+            render::print(game);
         }
-    } else if let Err(e) = result {
-        println!("Errors Occured: {:?}", e);
     }
 
-    //game.print();
+    show_results(game);
+}
+
+fn show_results(game: &mut Game) {
+    let results = match game.settle() {
+        Ok(results) => results,
+        Err(e) => {
+            println!("Errors Occured: {}", e);
+            wait_for_input();
+            return;
+        }
+    };
+    let dealer = game.dealer_status().ok();
+
+    for result in &results {
+        print!("{}", seat_name(game, result.seat));
+        if let Some(label) = game
+            .player(result.seat)
+            .and_then(|player| render::hand_label(player, result.hand))
+        {
+            print!("{}: ", label);
+        }
+
+        let message = match (result.outcome, result.status, dealer) {
+            (RoundOutcome::Blackjack, _, _) => "YOU GOT A BLACKJACK!",
+            (RoundOutcome::EvenMoney, _, _) => "You took even money.",
+            (RoundOutcome::Surrender, _, _) => "SURRENDERED",
+            (RoundOutcome::Push, HandStatus::Blackjack, _) => "You both got a blackjack! PUSH!",
+            (RoundOutcome::Push, _, _) => "PUSH!",
+            (RoundOutcome::Loss, HandStatus::Bust, _) => "BUSTED!!!!",
+            (RoundOutcome::Loss, _, Some(HandStatus::Blackjack)) => "THEY GOT A BLACKJACK!",
+            (RoundOutcome::Loss, _, Some(HandStatus::TwentyOne)) => "THEY GOT 21!!!!!!!!!!!!!!!",
+            (RoundOutcome::Loss, _, _) => "YOU LOST :(",
+            (RoundOutcome::Win, HandStatus::Live(_), Some(HandStatus::Bust)) => {
+                "THEY BUSTED EVERYWHERE!!!!"
+            }
+            (RoundOutcome::Win, HandStatus::Live(_), _) => "YOU WON! :)",
+            (RoundOutcome::Win, status, _) => status_message(status),
+        };
+
+        if let (HandStatus::Live(pt), Some(HandStatus::Live(dt))) = (result.status, dealer) {
+            println!("The results were: YOU: {}, DEALER: {}", pt, dt);
+        }
+        println!("{} ({:+})", message, result.net);
+    }
+
     wait_for_input();
-    restart(game);
+}
+
+fn status_message(status: HandStatus) -> &'static str {
+    match status {
+        HandStatus::Blackjack => "YOU GOT A BLACKJACK!",
+        HandStatus::TwentyOne => "YOU GOT 21!!!!!!!",
+        HandStatus::Charlie => "FIVE CARD CHARLIE!!!!",
+        HandStatus::Bust => "BUSTED!!!!",
+        HandStatus::Live(_) => "",
+    }
 }
 
 fn restart(game: &mut Game) {
-    game.clear();
-    game.deal();
+    if let Err(e) = game.clear() {
+        println!("Errors Occured: {}", e);
+        wait_for_input();
+    }
+    if game.needs_reshuffle() {
+        println!("The cut card came out! Shuffling the shoe...");
+        game.shuffle();
+        wait_for_input();
+    }
 }
 
+// Goes round the table taking bets, one or more boxes each. Returns false
+// once nobody is left playing.
 fn place_bets(game: &mut Game) -> bool {
-    loop {
-        println!("Bank: ${}", game.get_bank());
-        print!("How much are you betting OR Do you want out?: ");
-        stdout().flush().unwrap();
-
-        let mut inp = String::new();
-        stdin().read_line(&mut inp).unwrap();
+    let max_spots = game.rules().max_spots;
+    let min_bet = game.rules().min_bet;
+    let mut seat = 0;
+    while seat < game.seats() {
+        if let Err(e) = game.select_seat(seat) {
+            println!("Errors Occured: {}", e);
+            return false;
+        }
 
-        if inp.trim() == "out" {
-            return true;
+        if game.get_bank() < min_bet {
+            if game.seats() == 1 {
+                println!("The House wins!");
+                return false;
+            }
+            println!("{} is out of money and leaves the table.", name(game));
+            let _ = game.leave(seat);
+            continue;
         }
 
-        let amount = inp.trim().parse::<i32>();
+        let spot = game.player(seat).map_or(0, |player| player.bets().len());
+        if spot == 0 {
+            println!("{} - Bank: ${}", name(game), game.get_bank());
+            print!("How much are you betting OR Do you want out? (or 'stats'): ");
+        } else if spot < max_spots && game.get_bank() >= min_bet {
+            print!(
+                "Bet on box {} too, or press [ENTER] to play {}: ",
+                spot + 1,
+                if spot == 1 { "one box" } else { "these boxes" }
+            );
+        } else {
+            seat += 1;
+            continue;
+        }
+        stdout().flush().unwrap();
 
-        if let Err(e) = amount {
-            println!("Errors occured: {:?}", e);
-        } else if let Ok(amount) = amount {
-            let bank = game.get_bank();
+        let mut inp = String::new();
+        stdin().read_line(&mut inp).unwrap();
 
-            if amount == 0 {
-                println!("You cannot bet $0");
-            } else {
-                match bank.cmp(&amount) {
-                    Less => println!("Can't place the bet. Not enough money!"),
-                    Greater | Equal => {
-                        println!("Bets have been placed.");
-                        game.remove_bank(amount);
-                        break;
-                    }
+        match inp.trim() {
+            "stats" => {
+                render::clear_screen();
+                render::print_stats(game);
+                wait_for_input();
+                continue;
+            }
+            "" if spot > 0 => {
+                seat += 1;
+                continue;
+            }
+            "out" if spot == 0 => {
+                if game.seats() == 1 {
+                    println!("Goodbye!");
+                    return false;
                 }
+                println!("Goodbye {}!", name(game));
+                let _ = game.leave(seat);
+                continue;
             }
+            _ => {}
+        }
+
+        match inp.trim().parse::<i32>() {
+            Err(e) => println!("Errors occured: {}", e),
+            Ok(amount) => match game.place_bet(amount) {
+                Ok(()) => println!("Bets have been placed."),
+                Err(e) => println!("Can't place the bet: {}", e),
+            },
         }
     }
-    false
+    true
 }
@@ -0,0 +1,220 @@
+use std::io::{Write, stdout};
+use std::time::Duration;
+
+use crossterm::event::{self, Event};
+
+use ::Cards::Card;
+use ::Game::{Game, HandValue, Phase, Player, Stats};
+
+pub fn clear_screen() {
+    print!("\x1B[2J\x1B[1;1H");
+}
+
+pub fn print(game: &Game) {
+    if game.seats() == 1 {
+        println!("Bank: ${}", game.get_bank());
+    }
+    println!("\n\n");
+    // Only count what the player can actually see
+    let dealer: Vec<Card> = game
+        .dealer_hand()
+        .into_iter()
+        .filter(|card| !card.hidden)
+        .collect();
+    print_cards_side_by_side(&game.dealer_hand());
+    if let Ok(value) = HandValue::new(&dealer) {
+        println!("Dealer: {}", describe(value));
+    }
+    println!("\n\n");
+
+    for seat in 0..game.seats() {
+        let Some(player) = game.player(seat) else {
+            continue;
+        };
+        let turn = seat == game.current_seat()
+            && matches!(game.phase(), Phase::Insurance | Phase::PlayerTurn);
+
+        if game.seats() > 1 {
+            let marker = if turn { " <" } else { "" };
+            println!("{} - Bank: ${}{}", player.name, player.bank, marker);
+        }
+
+        for (i, hand) in player.hands.iter().enumerate() {
+            if let Some(label) = hand_label(player, i) {
+                let marker = if turn && i == player.current_hand() {
+                    " <"
+                } else {
+                    ""
+                };
+                println!("{} (${}){}", label, hand.wager, marker);
+            } else if hand.doubled {
+                println!("Doubled (${})", hand.wager);
+            }
+            print_cards_side_by_side(&hand.cards);
+            if let Ok(value) = hand.value() {
+                println!("You: {}", describe(value));
+            }
+        }
+        println!();
+    }
+}
+
+// Names a hand once there's more than one: "Box 2" for a second box and
+// "Hand 2" for the second hand split out of a box
+pub fn hand_label(player: &Player, index: usize) -> Option<String> {
+    let hand = player.hands.get(index)?;
+    let in_box = player
+        .hands
+        .iter()
+        .filter(|other| other.spot == hand.spot)
+        .count();
+    let first = player
+        .hands
+        .iter()
+        .position(|other| other.spot == hand.spot)?;
+
+    let mut parts = Vec::new();
+    if player.spots() > 1 {
+        parts.push(format!("Box {}", hand.spot + 1));
+    }
+    if in_box > 1 {
+        parts.push(format!("Hand {}", index - first + 1));
+    }
+
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(", "))
+    }
+}
+
+// One column per player, one row per number
+pub fn print_stats(game: &Game) {
+    let players: Vec<&Player> = (0..game.seats())
+        .filter_map(|seat| game.player(seat))
+        .collect();
+    let column = |cell: fn(&Stats) -> String| -> Vec<String> {
+        players.iter().map(|player| cell(&player.stats)).collect()
+    };
+    let rows = [
+        ("Hands played", column(|stats| stats.hands.to_string())),
+        ("Won", column(|stats| stats.wins.to_string())),
+        ("Lost", column(|stats| stats.losses.to_string())),
+        ("Pushed", column(|stats| stats.pushes.to_string())),
+        ("Blackjacks", column(|stats| stats.blackjacks.to_string())),
+        ("Busts", column(|stats| stats.busts.to_string())),
+        ("Doubles", column(|stats| stats.doubles.to_string())),
+        ("Splits", column(|stats| stats.splits.to_string())),
+        ("Net", column(|stats| money(stats.net))),
+        ("Biggest win", column(|stats| money(stats.biggest_win))),
+        ("Max drawdown", column(|stats| money(stats.max_drawdown))),
+        ("Time played", column(|stats| clock(stats.time))),
+    ];
+
+    let width = players
+        .iter()
+        .map(|player| player.name.len())
+        .max()
+        .unwrap_or(0)
+        .max(10)
+        + 2;
+    print!("{:<14}", "");
+    for player in &players {
+        print!("{:>width$}", player.name);
+    }
+    println!();
+
+    for (label, cells) in rows {
+        print!("{:<14}", label);
+        for cell in cells {
+            print!("{:>width$}", cell);
+        }
+        println!();
+    }
+}
+
+fn clock(time: Duration) -> String {
+    let seconds = time.as_secs();
+    format!(
+        "{}h {:02}m {:02}s",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+fn money(amount: i64) -> String {
+    if amount < 0 {
+        format!("-${}", -amount)
+    } else {
+        format!("${}", amount)
+    }
+}
+
+fn describe(value: HandValue) -> String {
+    if value.is_blackjack {
+        "Blackjack".to_string()
+    } else if value.is_bust {
+        format!("Bust ({})", value.total)
+    } else if value.is_soft {
+        format!("Soft {}", value.total)
+    } else {
+        value.total.to_string()
+    }
+}
+
+pub fn wait_for_seconds(seconds: u64) {
+    std::thread::sleep(Duration::from_secs(seconds));
+}
+
+pub fn wait_for_input() {
+    println!("Press [ENTER]! to continue: ");
+    stdout().flush().unwrap();
+
+    loop {
+        if event::poll(Duration::from_millis(10)).unwrap()
+            && let Event::Key(_) = event::read().unwrap()
+        {
+            break;
+        }
+    }
+    clear_screen();
+}
+
+fn print_cards_side_by_side(cards: &[Card]) {
+    let mut top_line = vec![];
+    let mut line1 = vec![];
+    let mut line2 = vec![];
+    let mut line3 = vec![];
+    let mut line4 = vec![];
+    let mut bottom_line = vec![];
+
+    for card in cards {
+        if card.hidden {
+            // Face-down with centered "HUGOS" and "CARDS"
+            top_line.push("+-------+".to_string());
+            line1.push("|       |".to_string());
+            line2.push("| HUGOS |".to_string());
+            line3.push("| CARDS |".to_string());
+            line4.push("|       |".to_string());
+            bottom_line.push("+-------+".to_string());
+        } else {
+            let label = card.short_label();
+            top_line.push("+-------+".to_string());
+            line1.push(format!("| {:<3}   |", label));
+            line2.push("|       |".to_string());
+            line3.push("|       |".to_string());
+            line4.push(format!("|   {:>3} |", label));
+            bottom_line.push("+-------+".to_string());
+        }
+    }
+
+    // Print all lines joined by spaces
+    println!();
+    println!("{}", top_line.join("  "));
+    println!("{}", line1.join("  "));
+    println!("{}", line2.join("  "));
+    println!("{}", line3.join("  "));
+    println!("{}", line4.join("  "));
+    println!("{}", bottom_line.join("  "));
+}
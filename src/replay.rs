@@ -0,0 +1,186 @@
+use std::fs::File;
+use std::io::{BufReader, Write, stdin, stdout};
+
+use ::Game::{Dealer, Errs, Event, Game, Player, Round, User, read_jsonl};
+
+use crate::render;
+
+// Plays a hand history exported by the game back, one move at a time
+pub fn run(path: &str) -> Result<(), String> {
+    let file = File::open(path).map_err(|e| format!("can't open {}: {}", path, e))?;
+    let rounds = read_jsonl(BufReader::new(file)).map_err(|e| format!("{}: {}", path, e))?;
+    if rounds.is_empty() {
+        return Err(format!("there are no rounds in {}", path));
+    }
+
+    let mut index = 0;
+    let mut step: usize = 0;
+    let mut steps = moves(&rounds[index])?;
+    loop {
+        let round = &rounds[index];
+        let upto = step.checked_sub(1).map(|step| steps[step]);
+        show(round, upto).map_err(|e| stuck(round, e))?;
+
+        println!();
+        match upto {
+            Some(event) => println!(
+                "Round {} of {}, move {} of {}: {}",
+                index + 1,
+                rounds.len(),
+                step,
+                steps.len(),
+                describe(round, &round.events[event])
+            ),
+            None => println!("Round {} of {}: the bets are down", index + 1, rounds.len()),
+        }
+        if step == steps.len() {
+            results(round);
+        }
+
+        print!("[ENTER] next, b back, n/p next or previous round, g N go to round N, q quit: ");
+        stdout().flush().unwrap();
+        let mut inp = String::new();
+        if stdin().read_line(&mut inp).unwrap_or(0) == 0 {
+            return Ok(());
+        }
+
+        let target = match inp.trim() {
+            "" if step < steps.len() => {
+                step += 1;
+                continue;
+            }
+            "" | "n" => index + 1,
+            "b" if step > 0 => {
+                step -= 1;
+                continue;
+            }
+            "b" | "p" => index.saturating_sub(1),
+            "q" => return Ok(()),
+            other => match other.strip_prefix('g').map(|n| n.trim().parse::<usize>()) {
+                Some(Ok(number)) if (1..=rounds.len()).contains(&number) => number - 1,
+                _ => {
+                    println!("That is not an input!");
+                    render::wait_for_input();
+                    continue;
+                }
+            },
+        };
+
+        if target >= rounds.len() {
+            println!("That was the last round.");
+            render::wait_for_input();
+            continue;
+        }
+        index = target;
+        step = 0;
+        steps = moves(&rounds[index])?;
+    }
+}
+
+fn stuck(round: &Round, e: Errs) -> String {
+    format!("round {} doesn't play back the same: {}", round.number, e)
+}
+
+// Sits the round's players back down with the banks they had and the same
+// bets in front of them
+fn restaged<T>(round: &Round, then: impl FnOnce(&mut Game) -> Result<T, Errs>) -> Result<T, Errs> {
+    let mut dealer = Dealer::new();
+    let mut players: Vec<Player> = round
+        .seats
+        .iter()
+        .map(|seat| {
+            let mut player = Player::new();
+            player.name = seat.name.clone();
+            player.bank = seat.bank;
+            player
+        })
+        .collect();
+
+    let (first, rest) = players.split_first_mut().ok_or(Errs::NoSuchSeat(0))?;
+    let mut game = Game::with_seed(round.rules, 0, &mut dealer, first);
+    for player in rest {
+        game.seat(player)?;
+    }
+    game.restage(round)?;
+    then(&mut game)
+}
+
+// Where in the round's events something happened that's worth a look
+pub fn moves(round: &Round) -> Result<Vec<usize>, String> {
+    restaged(round, |game| {
+        let mut moves = Vec::new();
+        for i in 0..round.events.len() {
+            if game.replay(round, i)? {
+                moves.push(i);
+            }
+        }
+        Ok(moves)
+    })
+    .map_err(|e| stuck(round, e))
+}
+
+// The table as it was once the events up to this one had happened
+fn show(round: &Round, upto: Option<usize>) -> Result<(), Errs> {
+    restaged(round, |game| {
+        if let Some(upto) = upto {
+            for i in 0..=upto {
+                game.replay(round, i)?;
+            }
+        }
+        render::clear_screen();
+        render::print(game);
+        Ok(())
+    })
+}
+
+fn describe(round: &Round, event: &Event) -> String {
+    let name = |seat: usize| {
+        round
+            .seats
+            .get(seat)
+            .map_or("Someone".to_string(), |seat| seat.name.clone())
+    };
+
+    match *event {
+        Event::PlayerCard { .. } | Event::HoleCard => "the cards are dealt".to_string(),
+        Event::DealerCard(card) => format!("the dealer draws {}", card.short_label()),
+        Event::HoleCardRevealed(card) => {
+            format!("the dealer turns over {}", card.short_label())
+        }
+        Event::Hit { seat, .. } => format!("{} hits", name(seat)),
+        Event::Stood { seat, .. } => format!("{} stands", name(seat)),
+        Event::Split { seat, .. } => format!("{} splits", name(seat)),
+        Event::Doubled { seat, .. } => format!("{} doubles", name(seat)),
+        Event::Surrendered { seat, refund, .. } => {
+            format!("{} surrenders and gets ${} back", name(seat), refund)
+        }
+        Event::InsurancePlaced { seat, amount, .. } => {
+            format!("{} takes ${} of insurance", name(seat), amount)
+        }
+        Event::InsuranceDeclined { seat, .. } => format!("{} passes on insurance", name(seat)),
+        Event::EvenMoney { seat, paid, .. } => {
+            format!("{} takes even money for ${}", name(seat), paid)
+        }
+        Event::InsuranceSettled { .. } => "the insurance is settled".to_string(),
+        Event::Settled(_) => "the bets are settled".to_string(),
+        Event::Shuffled => "the shoe is shuffled".to_string(),
+    }
+}
+
+fn results(round: &Round) {
+    for event in &round.events {
+        if let Event::Settled(result) = event {
+            let name = round
+                .seats
+                .get(result.seat)
+                .map_or("", |seat| seat.name.as_str());
+            println!(
+                "{} box {}: {:?} ({:+})",
+                name,
+                result.spot + 1,
+                result.outcome,
+                result.net
+            );
+        }
+    }
+}
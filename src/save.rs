@@ -0,0 +1,285 @@
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ::Cards::{Deck, Shoe};
+use ::Game::{Game, Player, Round, Stats, TableRules, User, write_csv, write_jsonl};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// Bumped whenever the layout changes, so older saves can be brought up to
+// date and newer ones aren't misread
+pub const VERSION: u32 = 2;
+
+#[derive(Debug)]
+pub enum SaveError {
+    NoDataDir,
+    BadName(String),
+    Io(io::Error),
+    Corrupt(serde_json::Error),
+    TooNew(u32),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::NoDataDir => write!(f, "couldn't find a data directory to save to"),
+            SaveError::BadName(name) => write!(
+                f,
+                "'{}' isn't a profile name, use letters, numbers, '-' and '_'",
+                name
+            ),
+            SaveError::Io(e) => write!(f, "{}", e),
+            SaveError::Corrupt(e) => write!(f, "the save file is damaged: {}", e),
+            SaveError::TooNew(version) => write!(
+                f,
+                "the save file is version {}, this game only reads up to {}",
+                version, VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<io::Error> for SaveError {
+    fn from(e: io::Error) -> Self {
+        SaveError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(e: serde_json::Error) -> Self {
+        SaveError::Corrupt(e)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedPlayer {
+    pub name: String,
+    pub bank: i32,
+    // The most the bank has ever held
+    pub peak: i32,
+    pub stats: Stats,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Save {
+    pub version: u32,
+    pub players: Vec<SavedPlayer>,
+    pub rules: TableRules,
+    pub shoe: Shoe,
+    pub sessions: u32,
+    pub rounds: u64,
+}
+
+impl Save {
+    pub fn new(game: &Game) -> Self {
+        let mut save = Self {
+            version: VERSION,
+            players: Vec::new(),
+            rules: *game.rules(),
+            shoe: game.shoe.clone(),
+            sessions: 0,
+            rounds: 0,
+        };
+        for seat in 0..game.seats() {
+            if let Some(player) = game.player(seat) {
+                save.players.push(SavedPlayer {
+                    name: player.name.clone(),
+                    bank: player.bank,
+                    peak: player.bank,
+                    stats: player.stats,
+                });
+            }
+        }
+        save
+    }
+
+    // Everyone sits back down with the bank they left with, and anyone who
+    // went broke gets a fresh stake
+    pub fn players(&self, stake: i32) -> Vec<Player> {
+        self.players
+            .iter()
+            .map(|saved| {
+                let mut player = Player::new();
+                player.name = saved.name.clone();
+                player.bank = if saved.bank > 0 { saved.bank } else { stake };
+                player.stats = saved.stats;
+                player
+            })
+            .collect()
+    }
+
+    // A shoe for a different number of decks than the table now uses is left
+    // for the fresh one the game already shuffled. One that's restored takes
+    // the table's penetration, in case that's been changed since.
+    pub fn restore_shoe(&self, game: &mut Game) -> bool {
+        let fits = self.shoe.num_decks() == game.rules().num_decks;
+        if fits {
+            game.shoe = self.shoe.clone();
+            game.shoe.set_penetration(game.rules().penetration);
+        }
+        fits
+    }
+
+    // Called between rounds, once the table has been cleared. Players who
+    // left keep the bank they left with.
+    pub fn update(&mut self, game: &Game) {
+        self.rounds += 1;
+        self.shoe = game.shoe.clone();
+        for seat in 0..game.seats() {
+            let Some(player) = game.player(seat) else {
+                continue;
+            };
+            if let Some(saved) = self
+                .players
+                .iter_mut()
+                .find(|saved| saved.name == player.name)
+            {
+                saved.bank = player.bank;
+                saved.peak = saved.peak.max(player.bank);
+                saved.stats = player.stats;
+            }
+        }
+    }
+
+    pub fn write(&self, profile: &str) -> Result<(), SaveError> {
+        let path = path(profile)?;
+        fs::create_dir_all(dir()?)?;
+
+        // Written alongside and moved into place, so a crash mid-write can't
+        // leave half a save behind
+        let temp = path.with_extension("json.tmp");
+        fs::write(&temp, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&temp, &path)?;
+        Ok(())
+    }
+
+    // Reads a save, bringing an older layout up to date
+    pub fn parse(text: &str) -> Result<Save, SaveError> {
+        let mut save: Value = serde_json::from_str(text)?;
+        let version = save.get("version").and_then(Value::as_u64).unwrap_or(0) as u32;
+        if version > VERSION {
+            return Err(SaveError::TooNew(version));
+        }
+
+        // Older layouts get upgraded here, one version at a time
+        if version < 2
+            && let Some(players) = save.get_mut("players").and_then(Value::as_array_mut)
+        {
+            // Stats weren't kept before version 2
+            for player in players.iter_mut().filter_map(Value::as_object_mut) {
+                player.insert("stats".to_string(), serde_json::to_value(Stats::default())?);
+            }
+        }
+        if let Some(fields) = save.as_object_mut() {
+            fields.insert("version".to_string(), VERSION.into());
+        }
+        let save: Save = serde_json::from_value(save)?;
+
+        let corrupt = |why: &str| SaveError::Corrupt(serde::de::Error::custom(why));
+        if save.players.is_empty() {
+            return Err(corrupt("there's nobody at the table"));
+        }
+        if !whole(&save.shoe) {
+            return Err(corrupt("the shoe isn't made up of whole decks"));
+        }
+        Ok(save)
+    }
+}
+
+// Every card of every deck, each exactly once, between the shoe and the
+// discards
+fn whole(shoe: &Shoe) -> bool {
+    let mut missing = Deck::new(shoe.num_decks()).cards;
+    for card in shoe.cards.iter().chain(&shoe.discards) {
+        let Some(i) = missing
+            .iter()
+            .position(|c| c.value == card.value && c.suit == card.suit)
+        else {
+            return false;
+        };
+        missing.swap_remove(i);
+    }
+    missing.is_empty()
+}
+
+fn data_dir() -> Result<PathBuf, SaveError> {
+    dirs::data_dir()
+        .map(|dir| dir.join("bj"))
+        .ok_or(SaveError::NoDataDir)
+}
+
+fn dir() -> Result<PathBuf, SaveError> {
+    Ok(data_dir()?.join("profiles"))
+}
+
+pub fn valid_name(profile: &str) -> bool {
+    !profile.is_empty()
+        && profile.len() <= 32
+        && profile
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn path(profile: &str) -> Result<PathBuf, SaveError> {
+    if !valid_name(profile) {
+        return Err(SaveError::BadName(profile.to_string()));
+    }
+    Ok(dir()?.join(format!("{}.json", profile)))
+}
+
+// The names of every saved profile, sorted
+pub fn profiles() -> Vec<String> {
+    let Ok(entries) = dir().and_then(|dir| fs::read_dir(dir).map_err(SaveError::from)) else {
+        return Vec::new();
+    };
+
+    let mut names: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+        .collect();
+    names.sort();
+    names
+}
+
+// None when there's no save for the profile yet
+pub fn load(profile: &str) -> Result<Option<Save>, SaveError> {
+    let path = path(profile)?;
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    Save::parse(&text).map(Some)
+}
+
+// Writes the session's hands out as JSON Lines and CSV, named after the
+// profile and when the session ended. Returns where they went.
+pub fn export_history(profile: &str, rounds: &[Round]) -> Result<Vec<PathBuf>, SaveError> {
+    path(profile)?;
+    let dir = data_dir()?.join("history");
+    fs::create_dir_all(&dir)?;
+
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs());
+    let stem = dir.join(format!("{}-{}", profile, time));
+
+    let jsonl = stem.with_extension("jsonl");
+    let mut out = io::BufWriter::new(fs::File::create(&jsonl)?);
+    write_jsonl(rounds, &mut out)?;
+    out.flush()?;
+
+    let csv = stem.with_extension("csv");
+    let mut out = io::BufWriter::new(fs::File::create(&csv)?);
+    write_csv(rounds, &mut out)?;
+    out.flush()?;
+
+    Ok(vec![jsonl, csv])
+}
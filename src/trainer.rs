@@ -0,0 +1,166 @@
+use std::io::{Write, stdin, stdout};
+use std::time::{Duration, Instant};
+
+use ::Cards::CountingSystem;
+use ::Game::Game;
+
+// Rounds between questions
+const EVERY: u32 = 3;
+
+#[derive(Default)]
+pub struct Score {
+    asked: u32,
+    correct: u32,
+    time: Duration,
+}
+
+impl Score {
+    pub fn record(&mut self, correct: bool, time: Duration) {
+        self.asked += 1;
+        self.correct += correct as u32;
+        self.time += time;
+    }
+
+    pub fn line(&self, name: &str) -> Option<String> {
+        if self.asked == 0 {
+            return None;
+        }
+
+        Some(format!(
+            "{}: {}/{} right ({:.0}%), {:.1}s per answer",
+            name,
+            self.correct,
+            self.asked,
+            self.correct as f32 / self.asked as f32 * 100.0,
+            self.time.as_secs_f32() / self.asked as f32
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Question {
+    Running,
+    True,
+}
+
+impl Question {
+    // Close enough is good enough for the true count, since nobody divides
+    // exactly at the table
+    pub fn right(self, answer: f32, count: f32) -> bool {
+        match self {
+            Question::Running => (answer - count).abs() < 0.01,
+            Question::True => (answer - count).abs() <= 0.5,
+        }
+    }
+}
+
+// Quizzes the player on the count every few rounds and keeps score
+pub struct Trainer {
+    system: &'static dyn CountingSystem,
+    rounds: u32,
+    running: Score,
+    true_count: Score,
+}
+
+impl Trainer {
+    pub fn new(system: &'static dyn CountingSystem) -> Self {
+        Self {
+            system,
+            rounds: 0,
+            running: Score::default(),
+            true_count: Score::default(),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.system.name()
+    }
+
+    // Counts a round and says what to ask after it, alternating between the
+    // running count and the true count. Unbalanced systems are played off the
+    // running count alone, so they never get asked for a true count.
+    pub fn next_question(&mut self) -> Option<Question> {
+        self.rounds += 1;
+        if !self.rounds.is_multiple_of(EVERY) {
+            return None;
+        }
+        if !self.system.balanced() || (self.rounds / EVERY) % 2 == 1 {
+            Some(Question::Running)
+        } else {
+            Some(Question::True)
+        }
+    }
+
+    // Called once a round is over
+    pub fn quiz(&mut self, game: &Game) {
+        let Some(question) = self.next_question() else {
+            return;
+        };
+
+        if question == Question::Running {
+            let count = game.running_count(self.system);
+            let (answer, time) = ask("What is the running count?");
+            let correct = answer.is_some_and(|answer| question.right(answer, count));
+            self.running.record(correct, time);
+            feedback(correct, &format!("{}", count));
+        } else {
+            let count = game.true_count(self.system);
+            let (answer, time) = ask("What is the true count?");
+            let correct = answer.is_some_and(|answer| question.right(answer, count));
+            self.true_count.record(correct, time);
+            feedback(
+                correct,
+                &format!(
+                    "{:.1} ({} over {:.1} decks)",
+                    count,
+                    game.running_count(self.system),
+                    game.decks_remaining()
+                ),
+            );
+        }
+    }
+
+    pub fn report(&self) {
+        println!("Counting with {} over {} rounds", self.name(), self.rounds);
+        let lines: Vec<String> = [
+            self.running.line("Running count"),
+            self.true_count.line("True count"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        if lines.is_empty() {
+            println!("No questions were asked.");
+        }
+        for line in lines {
+            println!("{}", line);
+        }
+    }
+}
+
+fn ask(question: &str) -> (Option<f32>, Duration) {
+    print!("{} ", question);
+    stdout().flush().unwrap();
+
+    let start = Instant::now();
+    let mut inp = String::new();
+    stdin().read_line(&mut inp).unwrap();
+    let time = start.elapsed();
+
+    (answer(&inp), time)
+}
+
+// A count as it's typed, with or without a plus sign
+pub fn answer(inp: &str) -> Option<f32> {
+    inp.trim().trim_start_matches('+').parse().ok()
+}
+
+fn feedback(correct: bool, answer: &str) {
+    if correct {
+        println!("Right!");
+    } else {
+        println!("Not quite, it was {}.", answer);
+    }
+    crate::render::wait_for_input();
+}
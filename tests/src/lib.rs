@@ -1,8 +1,59 @@
+#![cfg(test)]
+
 use ::Cards::*;
 use ::Game::*;
 
 #[cfg(test)]
 mod tests {
+    // Builders the modules below share. Cards are all one suit, since
+    // nothing here cares about suits.
+    mod common {
+        use crate::*;
+
+        pub fn card(value: Value) -> Card {
+            Card::new(value, Suit::Spades, false)
+        }
+
+        pub fn cards(values: &[Value]) -> Vec<Card> {
+            values.iter().map(|value| card(*value)).collect()
+        }
+
+        pub fn dealer(up: Value, hidden: Value) -> Dealer {
+            let hidden = Card::new(hidden, Suit::Clubs, true);
+            <Dealer as User>::from(vec![card(up)], Some(hidden), None).unwrap()
+        }
+
+        pub fn player(hand: &[Value], bank: i32) -> Player {
+            <Player as User>::from(cards(hand), None, Some(bank)).unwrap()
+        }
+
+        // Stands on whatever's left of the round and settles it
+        pub fn finish(game: &mut Game) {
+            while game.phase() == Phase::Insurance {
+                game.decline_insurance().unwrap();
+            }
+            while game.legal_actions().contains(&Action::Stand) {
+                game.player_stand().unwrap();
+            }
+            if game.phase() != Phase::Settled {
+                game.settle().unwrap();
+            }
+        }
+
+        // Moves these cards to the top of the shoe, first card dealt first.
+        // Each one is taken out from further down, so the shoe still holds
+        // its decks and nothing more.
+        pub fn stack(game: &mut Game, values: &[Value]) {
+            let cards = &mut game.shoe.cards;
+            for (stacked, value) in values.iter().rev().enumerate() {
+                let rest = &cards[..cards.len() - stacked];
+                let index = rest.iter().position(|card| card.value == *value);
+                cards.remove(index.unwrap_or(0));
+                cards.push(card(*value));
+            }
+        }
+    }
+
     mod dealer {
         mod checks {
             use crate::*;
@@ -24,8 +75,8 @@ mod tests {
                 match <Dealer as User>::from(hand, Some(hidden), None) {
                     Ok(mut dealer) => {
                         let mut player = Player::new();
-                        let mut game = Game::new(1, &mut dealer, &mut player);
-                        assert_eq!(game.dealer_checks(), Ok((false, false)));
+                        let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+                        assert_eq!(game.dealer_checks(), Ok(HandStatus::Live(20)));
                     }
                     Err(e) => println!("Errors Occurred: {:?}", e),
                 }
@@ -48,7 +99,7 @@ mod tests {
                 match <Dealer as User>::from(hand.clone(), Some(hidden), None) {
                     Ok(mut dealer) => {
                         let mut player = Player::new();
-                        let mut game = Game::new(1, &mut dealer, &mut player);
+                        let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
                         game.shuffle();
                         let _ = game.dealer_checks();
 
@@ -82,9 +133,9 @@ mod tests {
                 match <Dealer as User>::from(hand.clone(), Some(hidden), None) {
                     Ok(mut dealer) => {
                         let mut player = Player::new();
-                        let mut game = Game::new(1, &mut dealer, &mut player);
+                        let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
 
-                        assert_eq!(game.dealer_checks(), Ok((false, true)));
+                        assert_eq!(game.dealer_checks(), Ok(HandStatus::Bust));
                     }
                     Err(e) => println!("Errors Occurred: {:?}", e),
                 }
@@ -107,8 +158,8 @@ mod tests {
                 match <Dealer as User>::from(hand.clone(), Some(hidden), None) {
                     Ok(mut dealer) => {
                         let mut player = Player::new();
-                        let mut game = Game::new(1, &mut dealer, &mut player);
-                        assert_eq!(game.dealer_checks(), Ok((true, true)));
+                        let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+                        assert_eq!(game.dealer_checks(), Ok(HandStatus::Blackjack));
                     }
                     Err(e) => println!("Errors Occurred: {:?}", e),
                 }
@@ -152,8 +203,10 @@ mod tests {
                     Ok(mut dealer) => {
                         match <Player as User>::from(hand_p.clone(), None, Some(1000)) {
                             Ok(mut player) => {
-                                let mut game = Game::new(1, &mut dealer, &mut player);
-                                assert_eq!(game.dealer_checks(), Ok((true, false)));
+                                let mut game =
+                                    Game::new(TableRules::new(1), &mut dealer, &mut player);
+                                game.player_stand().unwrap();
+                                assert_eq!(game.dealer_checks(), Ok(HandStatus::TwentyOne));
                             }
                             Err(e) => println!("Errors Occurred: {:?}", e),
                         }
@@ -190,8 +243,8 @@ mod tests {
                 match <Player as User>::from(hand, None, Some(30)) {
                     Ok(mut player) => {
                         let mut dealer = Dealer::new();
-                        let mut game = Game::new(1, &mut dealer, &mut player);
-                        assert_eq!(game.player_checks(), Ok((false, true)));
+                        let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+                        assert_eq!(game.player_checks(), Ok(HandStatus::Bust));
                     }
                     Err(e) => println!("Errors Occurred: {:?}", e),
                 }
@@ -214,8 +267,8 @@ mod tests {
                 match <Player as User>::from(hand, None, Some(30)) {
                     Ok(mut player) => {
                         let mut dealer = Dealer::new();
-                        let mut game = Game::new(1, &mut dealer, &mut player);
-                        assert_eq!(game.player_checks(), Ok((true, false)));
+                        let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+                        assert_eq!(game.player_checks(), Ok(HandStatus::Blackjack));
                     }
                     Err(e) => println!("Errors Occurred: {:?}", e),
                 }
@@ -238,8 +291,8 @@ mod tests {
                 match <Player as User>::from(hand, None, Some(30)) {
                     Ok(mut player) => {
                         let mut dealer = Dealer::new();
-                        let mut game = Game::new(1, &mut dealer, &mut player);
-                        assert_eq!(game.player_checks(), Ok((false, false)));
+                        let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+                        assert_eq!(game.player_checks(), Ok(HandStatus::Live(19)));
                     }
                     Err(e) => println!("Errors Occurred: {:?}", e),
                 }
@@ -278,8 +331,8 @@ mod tests {
                 match <Player as User>::from(hand, None, Some(30)) {
                     Ok(mut player) => {
                         let mut dealer = Dealer::new();
-                        let mut game = Game::new(1, &mut dealer, &mut player);
-                        assert_eq!(game.player_checks(), Ok((true, false)));
+                        let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+                        assert_eq!(game.player_checks(), Ok(HandStatus::Charlie));
                     }
                     Err(e) => println!("Errors Occurred: {:?}", e),
                 }
@@ -287,69 +340,2971 @@ mod tests {
         }
     }
 
-    mod deck {
-        mod suits {
-            use crate::*;
+    mod settle {
+        use super::common::*;
+        use crate::*;
 
-            #[test]
-            fn removing_cards_when_dealt() {
-                let mut dealer = Dealer::new();
-                let mut player = Player::new();
-                let mut game = Game::new(1, &mut dealer, &mut player);
+        fn settle(dealer: &mut Dealer, player: &mut Player) -> (Settlement, i32) {
+            let mut game = Game::new(TableRules::new(1), dealer, player);
+            game.remove_bank(10);
+            game.shoe.cards = vec![card(Value::Five)];
+            // Offers are turned down, and a peeked blackjack ends the round
+            // before the player gets a turn
+            if game.phase() == Phase::Insurance {
+                game.decline_insurance().unwrap();
+            }
+            if game.phase() == Phase::PlayerTurn {
+                game.player_stand().unwrap();
+            }
 
-                game.deal();
+            let results = game.settle().unwrap();
+            assert_eq!(results.len(), 1);
+            (results[0], game.get_bank())
+        }
 
-                assert_eq!(game.deck.cards.len(), 48);
-            }
+        #[test]
+        fn win() {
+            let mut dealer = dealer(Value::King, Value::Eight);
+            let mut player = player(&[Value::King, Value::Nine], 100);
+            let (result, bank) = settle(&mut dealer, &mut player);
 
-            #[test]
-            fn count_hearts() {
-                let deck = Deck::new(1);
-                let count = deck
-                    .cards
-                    .iter()
-                    .filter(|card| card.suit == Suit::Hearts)
-                    .count();
+            assert_eq!(result.status, HandStatus::Live(19));
+            assert_eq!(result.outcome, RoundOutcome::Win);
+            assert_eq!(result.net, 10);
+            assert_eq!(bank, 110);
+        }
 
-                assert_eq!(count, 13);
-            }
+        #[test]
+        fn loss() {
+            let mut dealer = dealer(Value::King, Value::Nine);
+            let mut player = player(&[Value::King, Value::Eight], 100);
+            let (result, bank) = settle(&mut dealer, &mut player);
 
-            #[test]
-            fn count_spades() {
-                let deck = Deck::new(1);
-                let count = deck
-                    .cards
-                    .iter()
-                    .filter(|card| card.suit == Suit::Spades)
-                    .count();
+            assert_eq!(result.outcome, RoundOutcome::Loss);
+            assert_eq!(result.net, -10);
+            assert_eq!(bank, 90);
+        }
 
-                assert_eq!(count, 13);
-            }
+        #[test]
+        fn push() {
+            let mut dealer = dealer(Value::King, Value::Nine);
+            let mut player = player(&[Value::Queen, Value::Nine], 100);
+            let (result, bank) = settle(&mut dealer, &mut player);
 
-            #[test]
-            fn count_diamonds() {
-                let deck = Deck::new(1);
-                let count = deck
-                    .cards
-                    .iter()
-                    .filter(|card| card.suit == Suit::Diamonds)
-                    .count();
+            assert_eq!(result.outcome, RoundOutcome::Push);
+            assert_eq!(result.net, 0);
+            assert_eq!(bank, 100);
+        }
 
-                assert_eq!(count, 13);
-            }
+        #[test]
+        fn dealer_draws_and_busts() {
+            let mut dealer = dealer(Value::King, Value::Seven);
+            let mut player = player(&[Value::Ten, Value::Two], 100);
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+            game.remove_bank(10);
+            game.shoe.cards = vec![card(Value::Five)];
+            game.player_stand().unwrap();
 
-            #[test]
-            fn count_clubs() {
-                let deck = Deck::new(1);
-                let count = deck
-                    .cards
+            let results = game.settle().unwrap();
+            assert_eq!(game.dealer_status(), Ok(HandStatus::Live(17)));
+            assert_eq!(results[0].outcome, RoundOutcome::Loss);
+
+            drop(game);
+            let mut dealer = self::dealer(Value::King, Value::Six);
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+            game.shoe.cards = vec![card(Value::Nine)];
+            game.remove_bank(10);
+
+            let results = game.settle().unwrap();
+            assert_eq!(game.dealer_status(), Ok(HandStatus::Bust));
+            assert_eq!(results[0].outcome, RoundOutcome::Win);
+        }
+
+        #[test]
+        fn blackjack_pays_three_to_two() {
+            let mut dealer = dealer(Value::King, Value::Nine);
+            let mut player = player(&[Value::Ace, Value::King], 100);
+            let (result, bank) = settle(&mut dealer, &mut player);
+
+            assert_eq!(result.outcome, RoundOutcome::Blackjack);
+            assert_eq!(result.net, 15);
+            assert_eq!(bank, 115);
+        }
+
+        #[test]
+        fn blackjacks_push() {
+            let mut dealer = dealer(Value::Ace, Value::King);
+            let mut player = player(&[Value::Ace, Value::Queen], 100);
+            let (result, bank) = settle(&mut dealer, &mut player);
+
+            assert_eq!(result.outcome, RoundOutcome::Push);
+            assert_eq!(bank, 100);
+        }
+
+        #[test]
+        fn dealer_blackjack_beats_twenty_one() {
+            let mut dealer = dealer(Value::Ace, Value::King);
+            let mut player = player(&[Value::Seven, Value::Seven, Value::Seven], 100);
+            let (result, bank) = settle(&mut dealer, &mut player);
+
+            assert_eq!(result.status, HandStatus::TwentyOne);
+            assert_eq!(result.outcome, RoundOutcome::Loss);
+            assert_eq!(bank, 90);
+        }
+
+        #[test]
+        fn bust_loses_even_if_dealer_busts() {
+            let mut dealer = dealer(Value::King, Value::Six);
+            let mut player = player(&[Value::King, Value::Six, Value::Nine], 100);
+            let (result, bank) = settle(&mut dealer, &mut player);
+
+            assert_eq!(result.outcome, RoundOutcome::Loss);
+            assert_eq!(bank, 90);
+        }
+
+        #[test]
+        fn surrender() {
+            let rules = TableRules {
+                surrender: Surrender::Late,
+                ..TableRules::new(1)
+            };
+            let mut dealer = dealer(Value::King, Value::Nine);
+            let mut player = player(&[Value::King, Value::Six], 100);
+            let mut game = Game::new(rules, &mut dealer, &mut player);
+            game.remove_bank(10);
+            game.player_surrender().unwrap();
+
+            let results = game.settle().unwrap();
+            assert_eq!(results[0].outcome, RoundOutcome::Surrender);
+            assert_eq!(results[0].net, -5);
+            assert_eq!(game.get_bank(), 95);
+        }
+
+        #[test]
+        fn split_hands_settle_separately() {
+            let mut dealer = dealer(Value::King, Value::Seven);
+            let mut player = player(&[Value::Eight, Value::Eight], 100);
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+            game.remove_bank(10);
+            game.shoe.cards = vec![card(Value::Two), card(Value::Ten)];
+
+            game.player_split().unwrap();
+            game.player_stand().unwrap();
+            game.player_stand().unwrap();
+
+            let results = game.settle().unwrap();
+            assert_eq!(results[0].outcome, RoundOutcome::Win);
+            assert_eq!(results[1].outcome, RoundOutcome::Loss);
+            assert_eq!(game.get_bank(), 100);
+        }
+
+        #[test]
+        fn only_once() {
+            let mut dealer = dealer(Value::King, Value::Eight);
+            let mut player = player(&[Value::King, Value::Nine], 100);
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+            game.remove_bank(10);
+            game.player_stand().unwrap();
+
+            assert!(game.settle().is_ok());
+            assert_eq!(game.settle(), Err(Errs::AlreadySettled));
+            assert_eq!(game.get_bank(), 110);
+        }
+    }
+
+    mod split {
+        use super::common::*;
+        use crate::*;
+
+        fn pair(value: Value, bank: i32) -> Player {
+            player(&[value, value], bank)
+        }
+
+        #[test]
+        fn splits_a_pair() {
+            let mut dealer = dealer(Value::Ten, Value::Seven);
+            let mut player = pair(Value::Eight, 100);
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+            game.remove_bank(10);
+            stack(&mut game, &[Value::Three, Value::Nine]);
+
+            assert_eq!(game.player_split(), Ok(()));
+            assert_eq!(game.hand_count(), 2);
+            assert_eq!(game.current_hand(), 0);
+            assert_eq!(game.get_bank(), 80);
+            drop(game);
+
+            assert_eq!(
+                player.hands[0].cards,
+                vec![card(Value::Eight), card(Value::Three)]
+            );
+            assert_eq!(
+                player.hands[1].cards,
+                vec![card(Value::Eight), card(Value::Nine)]
+            );
+            assert!(
+                player
+                    .hands
                     .iter()
-                    .filter(|card| card.suit == Suit::Clubs)
-                    .count();
+                    .all(|hand| hand.wager == 10 && hand.split)
+            );
+        }
 
-                assert_eq!(count, 13);
+        #[test]
+        fn cannot_split_non_pair() {
+            let mut dealer = dealer(Value::Ten, Value::Seven);
+            let mut player = player(&[Value::Eight, Value::Nine], 100);
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+
+            assert_eq!(game.player_split(), Err(Errs::CannotSplit));
+            assert_eq!(game.hand_count(), 1);
+        }
+
+        #[test]
+        fn cannot_split_without_money() {
+            let mut dealer = dealer(Value::Ten, Value::Seven);
+            let mut player = pair(Value::Eight, 15);
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+            game.remove_bank(10);
+
+            assert_eq!(
+                game.player_split(),
+                Err(Errs::InsufficientFunds { bank: 5, bet: 10 })
+            );
+        }
+
+        #[test]
+        fn split_aces_get_one_card() {
+            let mut dealer = dealer(Value::Ten, Value::Seven);
+            let mut player = pair(Value::Ace, 100);
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+            game.remove_bank(10);
+            stack(&mut game, &[Value::Five, Value::King]);
+
+            assert_eq!(game.player_split(), Ok(()));
+            assert!(game.player_done());
+            assert!(!game.player_has_blackjack());
+            assert_eq!(
+                game.player_hit(),
+                Err(Errs::IllegalAction {
+                    phase: Phase::DealerTurn,
+                    action: Action::Hit
+                })
+            );
+            drop(game);
+
+            assert!(player.hands.iter().all(|hand| hand.cards.len() == 2));
+        }
+
+        // A hand that's already been split from aces and drawn another ace
+        fn split_aces() -> Player {
+            let mut player = pair(Value::Ace, 100);
+            player.hands[0].split = true;
+            player.hands[0].split_aces = true;
+            player.hands.push(player.hands[0].clone());
+            player
+        }
+
+        #[test]
+        fn no_resplitting_aces() {
+            let resplit = |resplit_aces: bool| {
+                let rules = TableRules {
+                    resplit_aces,
+                    ..TableRules::new(1)
+                };
+                let mut dealer = dealer(Value::Ten, Value::Seven);
+                let mut player = split_aces();
+                let mut game = Game::new(rules, &mut dealer, &mut player);
+                game.remove_bank(10);
+                assert_eq!(game.phase(), Phase::PlayerTurn);
+                (
+                    game.can_split(),
+                    game.legal_actions().contains(&Action::Split),
+                )
+            };
+
+            assert_eq!(resplit(false), (Err(Errs::CannotSplit), false));
+            assert_eq!(resplit(true), (Ok(()), true));
+        }
+
+        #[test]
+        fn resplitting_aces() {
+            let rules = TableRules {
+                resplit_aces: true,
+                ..TableRules::new(1)
+            };
+            let mut dealer = dealer(Value::Ten, Value::Seven);
+            let mut player = pair(Value::Ace, 100);
+            let mut game = Game::new(rules, &mut dealer, &mut player);
+            game.remove_bank(10);
+            stack(
+                &mut game,
+                &[Value::Ace, Value::Two, Value::Nine, Value::Five],
+            );
+
+            assert_eq!(game.player_split(), Ok(()));
+            assert_eq!(game.phase(), Phase::PlayerTurn);
+            assert_eq!(game.current_hand(), 0);
+            assert_eq!(game.legal_actions(), vec![Action::Stand, Action::Split]);
+            assert_eq!(game.player_hit(), Err(Errs::CannotHit));
+
+            assert_eq!(game.player_split(), Ok(()));
+            assert_eq!(game.hand_count(), 3);
+            assert_eq!(game.phase(), Phase::DealerTurn);
+            assert_eq!(game.get_bank(), 70);
+            drop(game);
+
+            let totals: Vec<i32> = player
+                .hands
+                .iter()
+                .map(|hand| hand.value().unwrap().total)
+                .collect();
+            assert_eq!(totals, vec![20, 16, 13]);
+        }
+
+        #[test]
+        fn resplit_aces_stop_at_the_limit() {
+            let rules = TableRules {
+                resplit_aces: true,
+                max_split_hands: 2,
+                ..TableRules::new(1)
+            };
+            let mut dealer = dealer(Value::Ten, Value::Seven);
+            let mut player = pair(Value::Ace, 100);
+            let mut game = Game::new(rules, &mut dealer, &mut player);
+            game.remove_bank(10);
+            stack(&mut game, &[Value::Ace, Value::Two]);
+
+            assert_eq!(game.player_split(), Ok(()));
+            assert!(game.player_done());
+            assert_eq!(game.phase(), Phase::DealerTurn);
+        }
+
+        #[test]
+        fn resplit_limit() {
+            let rules = TableRules {
+                max_split_hands: 3,
+                ..TableRules::new(1)
+            };
+            let mut dealer = dealer(Value::Ten, Value::Seven);
+            let mut player = pair(Value::Eight, 100);
+            let mut game = Game::new(rules, &mut dealer, &mut player);
+            game.remove_bank(10);
+            stack(
+                &mut game,
+                &[Value::Eight, Value::Two, Value::Eight, Value::Three],
+            );
+
+            assert_eq!(game.player_split(), Ok(()));
+            assert_eq!(game.player_split(), Ok(()));
+            assert_eq!(game.hand_count(), 3);
+            game.select_hand(1);
+            assert_eq!(game.player_split(), Err(Errs::CannotSplit));
+        }
+
+        #[test]
+        fn plays_each_hand_in_turn() {
+            let mut dealer = dealer(Value::Ten, Value::Seven);
+            let mut player = pair(Value::Eight, 100);
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+            game.remove_bank(10);
+            stack(&mut game, &[Value::Three, Value::Nine, Value::King]);
+
+            game.player_split().unwrap();
+            game.player_stand().unwrap();
+            assert_eq!(game.current_hand(), 1);
+            assert_eq!(game.player_hit(), Ok(HandStatus::Bust));
+            assert!(game.player_done());
+        }
+    }
+
+    mod double {
+        use super::common::*;
+        use crate::*;
+
+        #[test]
+        fn doubles_wager_and_draws_one() {
+            let mut dealer = dealer(Value::Ten, Value::Seven);
+            let mut player = player(&[Value::Six, Value::Five], 100);
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+            game.remove_bank(10);
+            game.shoe.cards = vec![card(Value::Two), card(Value::King)];
+
+            assert_eq!(game.player_double(), Ok(HandStatus::TwentyOne));
+            assert!(game.player_done());
+            assert_eq!(game.get_bank(), 80);
+
+            let results = game.settle().unwrap();
+            assert_eq!(results[0].outcome, RoundOutcome::Win);
+            assert_eq!(results[0].net, 20);
+            assert_eq!(game.get_bank(), 120);
+            drop(game);
+
+            assert_eq!(player.hands[0].cards.len(), 3);
+            assert_eq!(player.hands[0].wager, 20);
+            assert!(player.hands[0].doubled);
+        }
+
+        #[test]
+        fn only_on_two_cards() {
+            let mut dealer = dealer(Value::Ten, Value::Seven);
+            let mut player = player(&[Value::Two, Value::Three], 100);
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+            game.remove_bank(10);
+            game.shoe.cards = vec![card(Value::Four)];
+
+            game.player_hit().unwrap();
+            assert_eq!(game.player_double(), Err(Errs::CannotDouble));
+        }
+
+        #[test]
+        fn nine_to_eleven_only() {
+            let rules = TableRules {
+                double: Double::NineToEleven,
+                ..TableRules::new(1)
+            };
+
+            for (first, second, allowed) in [
+                (Value::Four, Value::Five, true),
+                (Value::Five, Value::Six, true),
+                (Value::Six, Value::Six, false),
+                (Value::Two, Value::Six, false),
+                (Value::Ace, Value::Seven, false),
+            ] {
+                let mut dealer = dealer(Value::Ten, Value::Seven);
+                let mut player = player(&[first, second], 100);
+                let mut game = Game::new(rules, &mut dealer, &mut player);
+                game.remove_bank(10);
+
+                assert_eq!(
+                    game.can_double().is_ok(),
+                    allowed,
+                    "{:?} {:?}",
+                    first,
+                    second
+                );
             }
         }
+
+        #[test]
+        fn not_after_split_without_das() {
+            let rules = TableRules {
+                double_after_split: false,
+                ..TableRules::new(1)
+            };
+            let mut dealer = dealer(Value::Ten, Value::Seven);
+            let mut player = player(&[Value::Five, Value::Five], 100);
+            let mut game = Game::new(rules, &mut dealer, &mut player);
+            game.remove_bank(10);
+            game.shoe.cards = vec![card(Value::Six), card(Value::Five)];
+
+            game.player_split().unwrap();
+            assert_eq!(game.player_double(), Err(Errs::CannotDouble));
+        }
+
+        #[test]
+        fn after_split_with_das() {
+            let mut dealer = dealer(Value::Ten, Value::Seven);
+            let mut player = player(&[Value::Five, Value::Five], 100);
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+            game.remove_bank(10);
+            game.shoe.cards = vec![card(Value::Nine), card(Value::Six), card(Value::Five)];
+
+            game.player_split().unwrap();
+            assert_eq!(game.player_double(), Ok(HandStatus::Live(19)));
+            assert_eq!(game.current_hand(), 1);
+            assert_eq!(game.get_bank(), 70);
+        }
+
+        #[test]
+        fn not_enough_money() {
+            let mut dealer = dealer(Value::Ten, Value::Seven);
+            let mut player = player(&[Value::Five, Value::Six], 15);
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+            game.remove_bank(10);
+
+            assert_eq!(
+                game.player_double(),
+                Err(Errs::InsufficientFunds { bank: 5, bet: 10 })
+            );
+        }
+    }
+
+    mod peek {
+        use super::common::*;
+        use crate::*;
+
+        #[test]
+        fn finds_blackjack_under_ten() {
+            let mut dealer = dealer(Value::King, Value::Ace);
+            let mut player = Player::new();
+            let game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+
+            assert!(game.dealer_peek());
+        }
+
+        #[test]
+        fn finds_blackjack_under_ace() {
+            let mut dealer = dealer(Value::Ace, Value::Queen);
+            let mut player = Player::new();
+            let game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+
+            assert!(game.dealer_peek());
+        }
+
+        #[test]
+        fn nothing_to_find() {
+            let mut dealer = dealer(Value::Ace, Value::Nine);
+            let mut player = Player::new();
+            let game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+
+            assert!(!game.dealer_peek());
+        }
+
+        #[test]
+        fn european_never_peeks() {
+            let rules = TableRules {
+                hole_card: HoleCard::European,
+                ..TableRules::new(1)
+            };
+            let mut dealer = dealer(Value::King, Value::Ace);
+            let mut player = Player::new();
+            let game = Game::new(rules, &mut dealer, &mut player);
+
+            assert!(!game.dealer_peek());
+        }
+
+        #[test]
+        fn european_deals_no_hole_card() {
+            let rules = TableRules {
+                hole_card: HoleCard::European,
+                ..TableRules::new(1)
+            };
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let mut game = Game::with_seed(rules, 5, &mut dealer, &mut player);
+
+            game.deal().unwrap();
+            assert_eq!(game.cards_left(), 49);
+            assert!(!game.has_blackjack());
+
+            game.dealer_reveal();
+            assert_eq!(game.cards_left(), 48);
+        }
+
+        fn eleven() -> Player {
+            player(&[Value::Five, Value::Six], 100)
+        }
+
+        #[test]
+        fn no_doubling_into_a_blackjack() {
+            let mut dealer = dealer(Value::Ace, Value::King);
+            let mut player = eleven();
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+            game.remove_bank(10);
+            game.shoe.cards = vec![card(Value::Ten)];
+
+            assert!(game.player_double().is_err());
+            game.decline_insurance().unwrap();
+            assert_eq!(game.phase(), Phase::DealerTurn);
+            assert!(game.player_double().is_err());
+
+            let results = game.settle().unwrap();
+            assert_eq!(results[0].net, -10);
+            assert_eq!(game.get_bank(), 90);
+        }
+
+        #[test]
+        fn moves_dont_give_the_hole_card_away() {
+            let actions = |hidden: Value| {
+                let mut dealer = dealer(Value::Ace, hidden);
+                let mut player = eleven();
+                let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+                game.remove_bank(10);
+                (game.legal_actions(), game.hint())
+            };
+
+            assert_eq!(actions(Value::King), actions(Value::Five));
+        }
+    }
+
+    mod insurance {
+        use super::common::*;
+        use crate::*;
+
+        #[test]
+        fn pays_two_to_one() {
+            let mut dealer = dealer(Value::Ace, Value::King);
+            let mut player = player(&[Value::Ten, Value::Nine], 100);
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+            game.remove_bank(20);
+
+            assert_eq!(game.place_insurance(10), Ok(()));
+            assert_eq!(game.get_bank(), 70);
+            assert_eq!(game.settle_insurance(), vec![(0, 20)]);
+            assert_eq!(game.get_bank(), 100);
+            assert_eq!(game.insurance(), 0);
+        }
+
+        #[test]
+        fn lost_without_blackjack() {
+            let mut dealer = dealer(Value::Ace, Value::Six);
+            let mut player = player(&[Value::Ten, Value::Nine], 100);
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+            game.remove_bank(20);
+
+            assert_eq!(game.place_insurance(10), Ok(()));
+            assert_eq!(game.settle_insurance(), vec![(0, -10)]);
+            assert_eq!(game.get_bank(), 70);
+        }
+
+        #[test]
+        fn partial_amounts() {
+            let mut dealer = dealer(Value::Ace, Value::King);
+            let mut player = player(&[Value::Ten, Value::Nine], 100);
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+            game.remove_bank(20);
+
+            assert_eq!(game.max_insurance(), 10);
+            assert_eq!(
+                game.place_insurance(11),
+                Err(Errs::InvalidInsurance {
+                    amount: 11,
+                    max: 10
+                })
+            );
+            assert_eq!(
+                game.place_insurance(0),
+                Err(Errs::InvalidInsurance { amount: 0, max: 10 })
+            );
+            assert_eq!(game.place_insurance(4), Ok(()));
+            assert_eq!(
+                game.place_insurance(4),
+                Err(Errs::IllegalAction {
+                    phase: Phase::DealerTurn,
+                    action: Action::Insurance
+                })
+            );
+            assert_eq!(game.settle_insurance(), vec![(0, 8)]);
+            assert_eq!(game.get_bank(), 88);
+        }
+
+        #[test]
+        fn settled_with_the_round() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let mut game = Game::with_seed(TableRules::new(1), 2, &mut dealer, &mut player);
+            stack(
+                &mut game,
+                &[Value::Ten, Value::Ace, Value::Nine, Value::King],
+            );
+            game.place_bet(20).unwrap();
+            game.deal().unwrap();
+            game.place_insurance(10).unwrap();
+
+            let results = game.settle().unwrap();
+            assert_eq!(results[0].outcome, RoundOutcome::Loss);
+            assert_eq!(game.get_bank(), 1000);
+            // Settling it again afterwards finds nothing left to pay
+            assert_eq!(game.settle_insurance(), vec![]);
+            let stats = game.player(0).unwrap().stats;
+            assert_eq!(stats.net, 0);
+            assert_eq!(stats.max_drawdown, 0);
+        }
+
+        #[test]
+        fn lost_with_the_round() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let mut game = Game::with_seed(TableRules::new(1), 2, &mut dealer, &mut player);
+            stack(
+                &mut game,
+                &[Value::Ten, Value::Ace, Value::Nine, Value::Six],
+            );
+            game.place_bet(20).unwrap();
+            game.deal().unwrap();
+            game.place_insurance(10).unwrap();
+            game.player_stand().unwrap();
+
+            let results = game.settle().unwrap();
+            assert_eq!(results[0].outcome, RoundOutcome::Win);
+            assert_eq!(game.get_bank(), 1010);
+            assert_eq!(game.player(0).unwrap().stats.net, 10);
+        }
+
+        #[test]
+        fn only_against_an_ace() {
+            let mut dealer = dealer(Value::King, Value::Ace);
+            let mut player = player(&[Value::Ten, Value::Nine], 100);
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+            game.remove_bank(20);
+
+            assert!(!game.can_insure());
+            assert_eq!(
+                game.place_insurance(5),
+                Err(Errs::IllegalAction {
+                    phase: Phase::DealerTurn,
+                    action: Action::Insurance
+                })
+            );
+        }
+
+        #[test]
+        fn even_money() {
+            let mut dealer = dealer(Value::Ace, Value::King);
+            let mut player = player(&[Value::Ace, Value::Queen], 100);
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+            game.remove_bank(20);
+
+            assert!(game.can_take_even_money());
+            assert_eq!(game.take_even_money(), Ok(20));
+            assert_eq!(game.get_bank(), 120);
+            assert!(game.player_done());
+        }
+
+        #[test]
+        fn no_even_money_without_blackjack() {
+            let mut dealer = dealer(Value::Ace, Value::King);
+            let mut player = player(&[Value::Ten, Value::Queen], 100);
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+            game.remove_bank(20);
+
+            assert_eq!(game.take_even_money(), Err(Errs::CannotInsure));
+        }
+
+        #[test]
+        fn european_settles_after_hole_card() {
+            let rules = TableRules {
+                hole_card: HoleCard::European,
+                ..TableRules::new(1)
+            };
+            let hidden = Card::new(Value::Nil, Suit::Nil, true);
+            let mut dealer =
+                <Dealer as User>::from(vec![card(Value::Ace)], Some(hidden), None).unwrap();
+            let mut player = player(&[Value::Ten, Value::Nine], 100);
+            let mut game = Game::new(rules, &mut dealer, &mut player);
+            game.remove_bank(20);
+            game.shoe.cards = vec![card(Value::Jack)];
+
+            assert_eq!(game.place_insurance(10), Ok(()));
+            assert_eq!(game.settle_insurance(), vec![]);
+
+            game.dealer_reveal();
+            assert_eq!(game.settle_insurance(), vec![(0, 20)]);
+        }
+    }
+
+    mod surrender {
+        use super::common::*;
+        use crate::*;
+
+        fn rules(surrender: Surrender) -> TableRules {
+            TableRules {
+                surrender,
+                ..TableRules::new(1)
+            }
+        }
+
+        #[test]
+        fn late_refunds_half() {
+            let mut dealer = dealer(Value::Ace, Value::Seven);
+            let mut player = player(&[Value::Ten, Value::Six], 100);
+            let mut game = Game::new(rules(Surrender::Late), &mut dealer, &mut player);
+            game.remove_bank(25);
+            game.decline_insurance().unwrap();
+
+            assert_eq!(game.player_surrender(), Ok(12));
+            assert_eq!(game.get_bank(), 87);
+            assert!(game.player_done());
+            assert!(game.hands()[0].surrendered);
+        }
+
+        #[test]
+        fn not_allowed_by_rules() {
+            let mut dealer = dealer(Value::Ace, Value::Seven);
+            let mut player = player(&[Value::Ten, Value::Six], 100);
+            let mut game = Game::new(rules(Surrender::None), &mut dealer, &mut player);
+            game.remove_bank(10);
+            game.decline_insurance().unwrap();
+
+            assert_eq!(game.player_surrender(), Err(Errs::CannotSurrender));
+            assert_eq!(game.get_bank(), 90);
+        }
+
+        #[test]
+        fn not_after_hitting() {
+            let mut dealer = dealer(Value::Ace, Value::Seven);
+            let mut player = player(&[Value::Ten, Value::Six], 100);
+            let mut game = Game::new(rules(Surrender::Late), &mut dealer, &mut player);
+            game.remove_bank(10);
+            game.shoe.cards = vec![card(Value::Two)];
+
+            game.decline_insurance().unwrap();
+            game.player_hit().unwrap();
+            assert_eq!(game.player_surrender(), Err(Errs::CannotSurrender));
+        }
+
+        #[test]
+        fn late_not_against_blackjack() {
+            let mut dealer = dealer(Value::Ace, Value::King);
+            let mut player = player(&[Value::Ten, Value::Six], 100);
+            let mut game = Game::new(rules(Surrender::Late), &mut dealer, &mut player);
+            game.remove_bank(10);
+
+            assert!(game.player_surrender().is_err());
+            game.decline_insurance().unwrap();
+            assert_eq!(game.phase(), Phase::DealerTurn);
+            assert!(game.player_surrender().is_err());
+            assert_eq!(game.get_bank(), 90);
+        }
+
+        #[test]
+        fn late_waits_for_the_peek() {
+            let offers = |hidden: Value| {
+                let mut dealer = dealer(Value::Ace, hidden);
+                let mut player = player(&[Value::Ten, Value::Six], 100);
+                let mut game = Game::new(rules(Surrender::Late), &mut dealer, &mut player);
+                game.remove_bank(10);
+                (game.can_surrender(), game.legal_actions(), game.hint())
+            };
+
+            let (surrender, actions, hint) = offers(Value::Five);
+            assert_eq!(
+                surrender,
+                Err(Errs::IllegalAction {
+                    phase: Phase::Insurance,
+                    action: Action::Surrender
+                })
+            );
+            assert!(!actions.contains(&Action::Surrender));
+            assert_eq!(hint, Some(Action::Decline));
+            assert_eq!(offers(Value::King), (surrender, actions, hint));
+        }
+
+        #[test]
+        fn early_against_blackjack() {
+            let mut dealer = dealer(Value::Ace, Value::King);
+            let mut player = player(&[Value::Ten, Value::Six], 100);
+            let mut game = Game::new(rules(Surrender::Early), &mut dealer, &mut player);
+            game.remove_bank(10);
+
+            assert_eq!(game.player_surrender(), Ok(5));
+            assert_eq!(game.get_bank(), 95);
+        }
+    }
+
+    mod events {
+        use super::common::*;
+        use crate::*;
+
+        #[test]
+        fn deal_reports_every_card() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let mut game = Game::with_seed(TableRules::new(1), 3, &mut dealer, &mut player);
+
+            game.deal().unwrap();
+            let hand = game.hands()[0].cards.clone();
+            let upcard = game.dealer_upcard().unwrap();
+
+            assert_eq!(
+                game.events(),
+                &[
+                    Event::PlayerCard {
+                        seat: 0,
+                        hand: 0,
+                        card: hand[0]
+                    },
+                    Event::DealerCard(upcard),
+                    Event::PlayerCard {
+                        seat: 0,
+                        hand: 0,
+                        card: hand[1]
+                    },
+                    Event::HoleCard,
+                ]
+            );
+        }
+
+        #[test]
+        fn stand_and_settle() {
+            let mut dealer = dealer(Value::King, Value::Seven);
+            let mut player = player(&[Value::Ten, Value::Nine], 100);
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+            game.remove_bank(10);
+
+            game.player_stand().unwrap();
+            let results = game.settle().unwrap();
+
+            let events = game.take_events();
+            assert_eq!(events[0], Event::Stood { seat: 0, hand: 0 });
+            assert_eq!(
+                events[1],
+                Event::HoleCardRevealed(Card::new(Value::Seven, Suit::Clubs, false))
+            );
+            assert_eq!(events[2], Event::Settled(results[0]));
+            assert_eq!(results[0].outcome, RoundOutcome::Win);
+            assert!(game.events().is_empty());
+        }
+    }
+
+    mod seats {
+        use super::common::*;
+        use crate::*;
+
+        // Seats everyone after the first, who sits down with Game::new. Cards
+        // go out a box at a time: first cards, upcard, second cards, hole card.
+        fn table<'a>(
+            dealer: &'a mut Dealer,
+            players: &'a mut [Player],
+            cards: &[Value],
+        ) -> Game<'a> {
+            let (first, rest) = players.split_first_mut().unwrap();
+            let mut game = Game::with_seed(TableRules::new(1), 1, dealer, first);
+            for player in rest {
+                game.seat(player).unwrap();
+            }
+            stack(&mut game, cards);
+            game
+        }
+
+        fn bet(game: &mut Game, amounts: &[i32]) {
+            for (seat, amount) in amounts.iter().enumerate() {
+                game.select_seat(seat).unwrap();
+                game.place_bet(*amount).unwrap();
+            }
+        }
+
+        #[test]
+        fn seven_at_most() {
+            let mut dealer = Dealer::new();
+            let mut players: Vec<Player> = (0..=MAX_SEATS).map(|_| Player::new()).collect();
+            let (first, rest) = players.split_first_mut().unwrap();
+            let mut game = Game::new(TableRules::new(1), &mut dealer, first);
+
+            let mut rest = rest.iter_mut();
+            for seat in 1..MAX_SEATS {
+                assert_eq!(game.seat(rest.next().unwrap()), Ok(seat));
+            }
+            assert_eq!(game.seat(rest.next().unwrap()), Err(Errs::TableFull));
+            assert_eq!(game.seats(), MAX_SEATS);
+        }
+
+        #[test]
+        fn deals_every_seat() {
+            let mut dealer = Dealer::new();
+            let mut players = vec![Player::new(), Player::new()];
+            let mut game = table(
+                &mut dealer,
+                &mut players,
+                &[
+                    Value::Nine,
+                    Value::Five,
+                    Value::Ten,
+                    Value::Eight,
+                    Value::Six,
+                    Value::Seven,
+                ],
+            );
+            bet(&mut game, &[10, 20]);
+            game.deal().unwrap();
+
+            assert_eq!(game.phase(), Phase::PlayerTurn);
+            assert_eq!(game.current_seat(), 0);
+            let first = game.player(0).unwrap();
+            assert_eq!(
+                first.hands[0].cards,
+                vec![card(Value::Nine), card(Value::Eight)]
+            );
+            assert_eq!(first.bank, 990);
+            let second = game.player(1).unwrap();
+            assert_eq!(
+                second.hands[0].cards,
+                vec![card(Value::Five), card(Value::Six)]
+            );
+            assert_eq!(second.hands[0].wager, 20);
+            assert_eq!(second.bank, 980);
+        }
+
+        #[test]
+        fn played_in_seat_order() {
+            let mut dealer = Dealer::new();
+            let mut players = vec![Player::new(), Player::new()];
+            let mut game = table(
+                &mut dealer,
+                &mut players,
+                &[
+                    Value::Nine,
+                    Value::Five,
+                    Value::Ten,
+                    Value::Eight,
+                    Value::Six,
+                    Value::Seven,
+                    Value::Nine,
+                ],
+            );
+            bet(&mut game, &[10, 10]);
+            game.deal().unwrap();
+
+            game.player_stand().unwrap();
+            assert_eq!(game.current_seat(), 1);
+            assert_eq!(game.player_hit(), Ok(HandStatus::Live(20)));
+            game.player_stand().unwrap();
+            assert_eq!(game.phase(), Phase::DealerTurn);
+
+            let results = game.settle().unwrap();
+            assert_eq!(results.len(), 2);
+            assert_eq!(
+                (results[0].seat, results[0].outcome),
+                (0, RoundOutcome::Push)
+            );
+            assert_eq!(
+                (results[1].seat, results[1].outcome),
+                (1, RoundOutcome::Win)
+            );
+            drop(game);
+
+            assert_eq!(players[0].bank, 1000);
+            assert_eq!(players[1].bank, 1010);
+        }
+
+        #[test]
+        fn insurance_goes_round_the_table() {
+            let mut dealer = Dealer::new();
+            let mut players = vec![Player::new(), Player::new()];
+            let mut game = table(
+                &mut dealer,
+                &mut players,
+                &[
+                    Value::Nine,
+                    Value::Five,
+                    Value::Ace,
+                    Value::Eight,
+                    Value::Six,
+                    Value::Seven,
+                ],
+            );
+            bet(&mut game, &[10, 10]);
+            game.deal().unwrap();
+
+            assert_eq!(game.phase(), Phase::Insurance);
+            assert_eq!(game.current_seat(), 0);
+            game.place_insurance(5).unwrap();
+            assert_eq!(game.current_seat(), 1);
+            assert_eq!(
+                game.player_hit(),
+                Err(Errs::IllegalAction {
+                    phase: Phase::Insurance,
+                    action: Action::Hit
+                })
+            );
+            game.decline_insurance().unwrap();
+
+            assert_eq!(game.phase(), Phase::PlayerTurn);
+            assert_eq!(game.current_seat(), 0);
+            assert_eq!(game.settle_insurance(), vec![(0, -5)]);
+        }
+
+        #[test]
+        fn leaving() {
+            let mut dealer = Dealer::new();
+            let mut players = vec![Player::new(), Player::new()];
+            let mut game = table(&mut dealer, &mut players, &[]);
+
+            assert_eq!(game.leave(2), Err(Errs::NoSuchSeat(2)));
+            game.select_seat(1).unwrap();
+            game.place_bet(50).unwrap();
+            assert_eq!(game.leave(1), Ok(()));
+            assert_eq!(game.seats(), 1);
+            assert_eq!(game.leave(0), Err(Errs::CannotLeave));
+            drop(game);
+
+            assert_eq!(players[1].bank, 1000);
+        }
+
+        #[test]
+        fn seats_only_change_while_betting() {
+            let mut dealer = Dealer::new();
+            let mut players = vec![Player::new(), Player::new()];
+            let mut game = table(
+                &mut dealer,
+                &mut players,
+                &[
+                    Value::Nine,
+                    Value::Five,
+                    Value::Ten,
+                    Value::Eight,
+                    Value::Six,
+                    Value::Seven,
+                ],
+            );
+            bet(&mut game, &[10, 10]);
+            game.deal().unwrap();
+
+            assert_eq!(game.select_seat(1), Err(Errs::OutOfTurn(1)));
+            assert_eq!(game.leave(1), Err(Errs::CannotLeave));
+        }
+    }
+
+    mod spots {
+        use super::common::*;
+        use crate::*;
+
+        fn bets(game: &mut Game, amounts: &[i32]) {
+            for amount in amounts {
+                game.place_bet(*amount).unwrap();
+            }
+        }
+
+        #[test]
+        fn dealt_in_casino_order() {
+            let mut dealer = Dealer::new();
+            let mut players = [Player::new(), Player::new()];
+            let (first, rest) = players.split_first_mut().unwrap();
+            let mut game = Game::with_seed(TableRules::new(1), 1, &mut dealer, first);
+            game.seat(&mut rest[0]).unwrap();
+            stack(
+                &mut game,
+                &[
+                    Value::Two,
+                    Value::Three,
+                    Value::Four,
+                    Value::Ten,
+                    Value::Five,
+                    Value::Six,
+                    Value::Seven,
+                    Value::Eight,
+                ],
+            );
+
+            bets(&mut game, &[10, 20]);
+            game.select_seat(1).unwrap();
+            bets(&mut game, &[5]);
+            game.deal().unwrap();
+
+            let dealt: Vec<(usize, usize, Value)> = game
+                .events()
+                .iter()
+                .filter_map(|event| match event {
+                    Event::PlayerCard { seat, hand, card } => Some((*seat, *hand, card.value)),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(
+                dealt,
+                vec![
+                    (0, 0, Value::Two),
+                    (0, 1, Value::Three),
+                    (1, 0, Value::Four),
+                    (0, 0, Value::Five),
+                    (0, 1, Value::Six),
+                    (1, 0, Value::Seven),
+                ]
+            );
+            assert_eq!(game.events()[3], Event::DealerCard(card(Value::Ten)));
+            assert_eq!(game.events().last(), Some(&Event::HoleCard));
+
+            let player = game.player(0).unwrap();
+            assert_eq!(player.spots(), 2);
+            assert_eq!(player.hands[1].spot, 1);
+            assert_eq!(player.hands[1].wager, 20);
+            assert_eq!(player.bank, 970);
+        }
+
+        #[test]
+        fn boxes_played_in_turn() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let mut game = Game::with_seed(TableRules::new(1), 1, &mut dealer, &mut player);
+            stack(
+                &mut game,
+                &[
+                    Value::Ten,
+                    Value::Eight,
+                    Value::Ten,
+                    Value::Nine,
+                    Value::Nine,
+                    Value::Eight,
+                ],
+            );
+            bets(&mut game, &[10, 10]);
+            game.deal().unwrap();
+
+            assert_eq!(game.current_hand(), 0);
+            game.player_stand().unwrap();
+            assert_eq!(game.current_hand(), 1);
+            assert_eq!(game.phase(), Phase::PlayerTurn);
+            game.player_stand().unwrap();
+            assert_eq!(game.phase(), Phase::DealerTurn);
+
+            let results = game.settle().unwrap();
+            let outcomes: Vec<(usize, RoundOutcome)> = results
+                .iter()
+                .map(|result| (result.spot, result.outcome))
+                .collect();
+            assert_eq!(
+                outcomes,
+                vec![(0, RoundOutcome::Win), (1, RoundOutcome::Loss)]
+            );
+        }
+
+        #[test]
+        fn splits_stay_in_their_box() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let mut game = Game::with_seed(TableRules::new(1), 1, &mut dealer, &mut player);
+            stack(
+                &mut game,
+                &[
+                    Value::Eight,
+                    Value::Ten,
+                    Value::Ten,
+                    Value::Eight,
+                    Value::Nine,
+                    Value::Seven,
+                    Value::Two,
+                    Value::Three,
+                ],
+            );
+            bets(&mut game, &[10, 10]);
+            game.deal().unwrap();
+            game.player_split().unwrap();
+
+            let spots: Vec<usize> = game.hands().iter().map(|hand| hand.spot).collect();
+            assert_eq!(spots, vec![0, 0, 1]);
+            assert_eq!(game.player(0).unwrap().spots(), 2);
+        }
+
+        #[test]
+        fn split_limit_is_per_box() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let rules = TableRules {
+                max_split_hands: 2,
+                ..TableRules::new(1)
+            };
+            let mut game = Game::with_seed(rules, 1, &mut dealer, &mut player);
+            stack(
+                &mut game,
+                &[
+                    Value::Eight,
+                    Value::Eight,
+                    Value::Ten,
+                    Value::Eight,
+                    Value::Eight,
+                    Value::Seven,
+                    Value::Ten,
+                    Value::Nine,
+                    Value::Ten,
+                    Value::Nine,
+                ],
+            );
+            bets(&mut game, &[10, 10]);
+            game.deal().unwrap();
+
+            game.player_split().unwrap();
+            game.player_stand().unwrap();
+            game.player_stand().unwrap();
+            assert_eq!(game.current_hand(), 2);
+            assert_eq!(game.can_split(), Ok(()));
+            game.player_split().unwrap();
+
+            let spots: Vec<usize> = game.hands().iter().map(|hand| hand.spot).collect();
+            assert_eq!(spots, vec![0, 0, 1, 1]);
+        }
+
+        #[test]
+        fn insured_box_by_box() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let mut game = Game::with_seed(TableRules::new(1), 1, &mut dealer, &mut player);
+            stack(
+                &mut game,
+                &[
+                    Value::Ten,
+                    Value::Nine,
+                    Value::Ace,
+                    Value::Eight,
+                    Value::Nine,
+                    Value::Seven,
+                ],
+            );
+            bets(&mut game, &[10, 20]);
+            game.deal().unwrap();
+
+            assert_eq!(game.phase(), Phase::Insurance);
+            assert_eq!(game.max_insurance(), 5);
+            game.place_insurance(5).unwrap();
+            assert_eq!(game.current_hand(), 1);
+            assert_eq!(game.max_insurance(), 10);
+            game.place_insurance(10).unwrap();
+
+            assert_eq!(game.phase(), Phase::PlayerTurn);
+            assert_eq!(game.current_hand(), 0);
+            assert_eq!(game.settle_insurance(), vec![(0, -15)]);
+            assert_eq!(game.get_bank(), 955);
+        }
+    }
+
+    mod phase {
+        use super::common::*;
+        use crate::*;
+
+        #[test]
+        fn betting_then_dealing() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let mut game = Game::with_seed(TableRules::new(1), 4, &mut dealer, &mut player);
+
+            assert_eq!(game.phase(), Phase::Betting);
+            assert_eq!(
+                game.legal_actions(),
+                vec![Action::Bet, Action::Deal, Action::Clear]
+            );
+            assert_eq!(game.place_bet(0), Err(Errs::InvalidBet(0)));
+            assert_eq!(
+                game.place_bet(5000),
+                Err(Errs::InsufficientFunds {
+                    bank: 1000,
+                    bet: 5000
+                })
+            );
+            assert_eq!(game.place_bet(10), Ok(()));
+            assert_eq!(
+                game.legal_actions(),
+                vec![Action::Bet, Action::Deal, Action::Clear]
+            );
+            game.place_bet(10).unwrap();
+            game.place_bet(10).unwrap();
+            assert_eq!(game.legal_actions(), vec![Action::Deal, Action::Clear]);
+            assert_eq!(game.place_bet(10), Err(Errs::TooManySpots(3)));
+            assert_eq!(
+                game.player_hit(),
+                Err(Errs::IllegalAction {
+                    phase: Phase::Betting,
+                    action: Action::Hit
+                })
+            );
+        }
+
+        #[test]
+        fn no_deal_without_clearing() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let mut game = Game::with_seed(TableRules::new(1), 4, &mut dealer, &mut player);
+
+            game.deal().unwrap();
+            assert_ne!(game.phase(), Phase::Betting);
+            assert!(matches!(
+                game.deal(),
+                Err(Errs::IllegalAction {
+                    action: Action::Deal,
+                    ..
+                })
+            ));
+            assert!(game.clear().is_err());
+
+            finish(&mut game);
+            game.clear().unwrap();
+            assert_eq!(game.phase(), Phase::Betting);
+            assert_eq!(game.deal(), Ok(()));
+        }
+
+        #[test]
+        fn no_clearing_mid_round() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let mut game = Game::with_seed(TableRules::new(1), 4, &mut dealer, &mut player);
+            stack(
+                &mut game,
+                &[Value::Ten, Value::Nine, Value::Eight, Value::Seven],
+            );
+            game.place_bet(100).unwrap();
+            game.deal().unwrap();
+
+            assert_eq!(game.phase(), Phase::PlayerTurn);
+            assert_eq!(
+                game.clear(),
+                Err(Errs::IllegalAction {
+                    phase: Phase::PlayerTurn,
+                    action: Action::Clear
+                })
+            );
+            assert_eq!(game.phase(), Phase::PlayerTurn);
+            assert_eq!(game.get_bank(), 900);
+            assert!(game.history().is_empty());
+        }
+
+        #[test]
+        fn clearing_before_the_deal_hands_bets_back() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let mut game = Game::with_seed(TableRules::new(1), 4, &mut dealer, &mut player);
+            game.place_bet(100).unwrap();
+            game.place_bet(50).unwrap();
+
+            assert_eq!(game.clear(), Ok(()));
+            assert_eq!(game.get_bank(), 1000);
+            assert_eq!(
+                game.legal_actions(),
+                vec![Action::Bet, Action::Deal, Action::Clear]
+            );
+            assert!(game.history().is_empty());
+        }
+
+        #[test]
+        fn legal_moves_on_a_pair() {
+            let mut dealer = dealer(Value::Six, Value::Seven);
+            let mut player = player(&[Value::Eight, Value::Eight], 100);
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+            game.remove_bank(10);
+
+            assert_eq!(game.phase(), Phase::PlayerTurn);
+            assert_eq!(
+                game.legal_actions(),
+                vec![Action::Hit, Action::Stand, Action::Double, Action::Split]
+            );
+        }
+
+        #[test]
+        fn no_hitting_after_a_bust() {
+            let mut dealer = dealer(Value::Six, Value::Seven);
+            let mut player = player(&[Value::King, Value::Six], 100);
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+            game.remove_bank(10);
+            game.shoe.cards.push(card(Value::Queen));
+
+            assert_eq!(game.player_hit(), Ok(HandStatus::Bust));
+            assert_eq!(game.phase(), Phase::DealerTurn);
+            assert_eq!(
+                game.legal_actions(),
+                vec![Action::DealerHit, Action::Settle]
+            );
+            assert!(game.player_hit().is_err());
+
+            game.settle().unwrap();
+            assert_eq!(game.phase(), Phase::Settled);
+            assert_eq!(game.legal_actions(), vec![Action::Clear]);
+        }
+
+        #[test]
+        fn insurance_has_to_be_answered_first() {
+            let mut dealer = dealer(Value::Ace, Value::Seven);
+            let mut player = player(&[Value::Two, Value::Three], 100);
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+            game.remove_bank(10);
+            game.shoe.cards.push(card(Value::Four));
+
+            assert_eq!(game.phase(), Phase::Insurance);
+            assert!(game.legal_actions().contains(&Action::Insurance));
+            assert_eq!(
+                game.player_hit(),
+                Err(Errs::IllegalAction {
+                    phase: Phase::Insurance,
+                    action: Action::Hit
+                })
+            );
+
+            game.decline_insurance().unwrap();
+            assert_eq!(game.phase(), Phase::PlayerTurn);
+            assert!(!game.can_insure());
+            game.player_hit().unwrap();
+        }
+    }
+
+    mod strategy {
+        use super::common::*;
+        use crate::*;
+
+        fn hand(a: Value, b: Value) -> Hand {
+            Hand::new(vec![card(a), card(b)], 10)
+        }
+
+        // Textbook tables don't have five-card charlie
+        fn rules() -> TableRules {
+            let mut rules = TableRules::new(6);
+            rules.five_card_charlie = false;
+            rules
+        }
+
+        #[test]
+        fn textbook_plays() {
+            let strategy = Strategy::new(rules());
+            let best = |a, b, upcard| strategy.best(&hand(a, b), upcard, true, &Action::ALL);
+
+            assert_eq!(best(Value::Ten, Value::Six, Value::Ten), Some(Action::Hit));
+            assert_eq!(
+                best(Value::Ten, Value::Two, Value::Five),
+                Some(Action::Stand)
+            );
+            assert_eq!(best(Value::Ten, Value::Two, Value::Two), Some(Action::Hit));
+            assert_eq!(
+                best(Value::Six, Value::Five, Value::Six),
+                Some(Action::Double)
+            );
+            assert_eq!(
+                best(Value::Eight, Value::Eight, Value::Ten),
+                Some(Action::Split)
+            );
+            assert_eq!(
+                best(Value::Ace, Value::Eight, Value::Six),
+                Some(Action::Stand)
+            );
+            assert_eq!(
+                best(Value::King, Value::Queen, Value::Six),
+                Some(Action::Stand)
+            );
+        }
+
+        #[test]
+        fn only_suggests_allowed_moves() {
+            let mut rules = rules();
+            rules.surrender = Surrender::Late;
+            let strategy = Strategy::new(rules);
+            let sixteen = hand(Value::Ten, Value::Six);
+
+            assert_eq!(
+                strategy.best(&sixteen, Value::Ten, true, &Action::ALL),
+                Some(Action::Surrender)
+            );
+            assert_eq!(
+                strategy.best(&sixteen, Value::Ten, true, &[Action::Hit, Action::Stand]),
+                Some(Action::Hit)
+            );
+            assert_eq!(strategy.best(&sixteen, Value::Ten, true, &[]), None);
+        }
+
+        #[test]
+        fn no_hole_card_doubles_less() {
+            let mut rules = rules();
+            let eleven = hand(Value::Six, Value::Five);
+            let peek = Strategy::new(rules);
+            rules.hole_card = HoleCard::European;
+            let european = Strategy::new(rules);
+
+            assert_eq!(
+                peek.best(&eleven, Value::Ten, true, &Action::ALL),
+                Some(Action::Double)
+            );
+            assert_eq!(
+                european.best(&eleven, Value::Ten, false, &Action::ALL),
+                Some(Action::Hit)
+            );
+        }
+
+        #[test]
+        fn nothing_to_say_about_finished_hands() {
+            let strategy = Strategy::new(rules());
+
+            assert!(
+                strategy
+                    .evaluate(&hand(Value::Ace, Value::King), Value::Six, true)
+                    .is_empty()
+            );
+        }
+
+        #[test]
+        fn game_hints() {
+            let mut dealer = dealer(Value::Ten, Value::Seven);
+            let mut player = player(&[Value::Ten, Value::Six], 100);
+            let game = Game::new(rules(), &mut dealer, &mut player);
+
+            assert_eq!(game.hint(), Some(Action::Hit));
+        }
+
+        #[test]
+        fn never_insures() {
+            let mut dealer = dealer(Value::Ace, Value::Seven);
+            let mut player = player(&[Value::Ten, Value::Nine], 100);
+            let game = Game::new(rules(), &mut dealer, &mut player);
+
+            assert_eq!(game.phase(), Phase::Insurance);
+            assert_eq!(game.hint(), Some(Action::Decline));
+        }
+    }
+
+    mod counting {
+        use super::common::*;
+        use crate::*;
+
+        #[test]
+        fn balanced_systems() {
+            for system in SYSTEMS {
+                let expected = if system.name() == "KO" { 4.0 } else { 0.0 };
+                assert_eq!(system.deck_total(), expected, "{}", system.name());
+            }
+            assert_eq!(KnockOut.initial_count(6), -20.0);
+            assert_eq!(WongHalves.tag(Value::Five), 1.5);
+            assert_eq!(
+                counting_system("omega ii").map(|s| s.name()),
+                Some("Omega II")
+            );
+            assert!(counting_system("red seven").is_none());
+        }
+
+        #[test]
+        fn counts_what_left_the_shoe() {
+            // Unshuffled, so the spade face cards come off the top
+            let mut shoe = Shoe::new(1, 0.75);
+            assert_eq!(shoe.running_count(&HiLo), 0.0);
+
+            for _ in 0..3 {
+                shoe.draw();
+            }
+            assert_eq!(shoe.running_count(&HiLo), -3.0);
+            assert_eq!(shoe.running_count(&Zen), -6.0);
+            assert_eq!(shoe.decks_remaining(), 49.0 / 52.0);
+            assert_eq!(shoe.true_count(&HiLo), -3.0 / (49.0 / 52.0));
+
+            // The king goes back in, the queen and jack are still on the table
+            shoe.discard([card(Value::King)]);
+            shoe.shuffle_seeded(1);
+            assert_eq!(shoe.running_count(&HiLo), -2.0);
+        }
+
+        #[test]
+        fn knock_out_starts_below_zero() {
+            let shoe = Shoe::new(6, 0.75);
+            assert_eq!(shoe.running_count(&KnockOut), -20.0);
+            assert_eq!(shoe.running_count(&HiLo), 0.0);
+        }
+
+        #[test]
+        fn hole_card_counts_once_seen() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let mut game = Game::with_seed(TableRules::new(1), 5, &mut dealer, &mut player);
+            let top = [Value::Two, Value::Ten, Value::Three, Value::Ace];
+            stack(&mut game, &top);
+            let before = game.running_count(&HiLo);
+
+            game.place_bet(10).unwrap();
+            game.deal().unwrap();
+            assert_eq!(game.running_count(&HiLo), before + 1.0);
+            // The hole card hasn't been seen yet
+            assert_eq!(game.decks_remaining(), 49.0 / 52.0);
+
+            game.dealer_reveal();
+            assert_eq!(game.running_count(&HiLo), before);
+        }
+    }
+
+    mod sim {
+        use crate::*;
+
+        fn run(seed: u64, threads: usize) -> Tally {
+            let config = Simulation {
+                rounds: 25_000,
+                seed,
+                threads,
+                play: Play::Basic,
+                rules: TableRules::new(6),
+            };
+            simulate(&config).unwrap()
+        }
+
+        #[test]
+        fn same_seed_same_results() {
+            let one = run(7, 1);
+            assert_eq!(one.rounds, 25_000);
+            assert_eq!(run(7, 1), one);
+        }
+
+        #[test]
+        fn threads_dont_change_the_results() {
+            assert_eq!(run(7, 4), run(7, 1));
+            assert_ne!(run(8, 1), run(7, 1));
+        }
+    }
+
+    mod history {
+        use super::common::*;
+        use crate::*;
+
+        // Player 10 then 6, dealer 9 up and 8 in the hole, player hits a 3
+        fn played(game: &mut Game) -> Vec<Settlement> {
+            let top = [
+                Value::Ten,
+                Value::Nine,
+                Value::Six,
+                Value::Eight,
+                Value::Three,
+            ];
+            stack(game, &top);
+
+            game.place_bet(10).unwrap();
+            game.deal().unwrap();
+            game.player_hit().unwrap();
+            game.player_stand().unwrap();
+            game.dealer_reveal();
+            game.settle().unwrap()
+        }
+
+        #[test]
+        fn records_each_round() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            player.name = "Ann".to_string();
+            let mut game = Game::with_seed(TableRules::new(1), 2, &mut dealer, &mut player);
+
+            let results = played(&mut game);
+            // The round goes into the history once the table is cleared
+            assert!(game.history().is_empty());
+            game.clear().unwrap();
+            game.take_events();
+
+            let round = &game.history()[0];
+            assert_eq!(round.number, 1);
+            assert_eq!(
+                round.seats,
+                vec![Seat {
+                    name: "Ann".to_string(),
+                    bank: 1000,
+                    bets: vec![10],
+                }]
+            );
+            assert_eq!(round.events[1], Event::DealerCard(card(Value::Nine)));
+            assert_eq!(round.events[4], Event::Hit { seat: 0, hand: 0 });
+            assert_eq!(round.events.last(), Some(&Event::Settled(results[0])));
+
+            played(&mut game);
+            game.clear().unwrap();
+            assert_eq!(game.history().len(), 2);
+            assert_eq!(game.history()[1].number, 2);
+            assert_eq!(game.history()[1].seats[0].bank, 1010);
+        }
+
+        #[test]
+        fn exports() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            player.name = "Smith, J".to_string();
+            let mut game = Game::with_seed(TableRules::new(1), 2, &mut dealer, &mut player);
+            played(&mut game);
+            game.clear().unwrap();
+            let round = &game.history()[0];
+
+            let mut csv = Vec::new();
+            write_csv(game.history(), &mut csv).unwrap();
+            let csv = String::from_utf8(csv).unwrap();
+            let rows: Vec<&str> = csv.lines().collect();
+            assert_eq!(rows[0], "round,seat,player,hand,event,card,amount,outcome");
+            assert_eq!(rows[1], "1,0,\"Smith, J\",0,bet,,10,");
+            assert_eq!(rows[3], "1,,Dealer,,dealer card,9s,,");
+            assert_eq!(rows.last(), Some(&"1,0,\"Smith, J\",0,settle,,10,Win"));
+            assert_eq!(rows.len(), 2 + round.events.len());
+
+            let mut jsonl = Vec::new();
+            write_jsonl(game.history(), &mut jsonl).unwrap();
+            let jsonl = String::from_utf8(jsonl).unwrap();
+            assert_eq!(jsonl.lines().count(), 1);
+            let read: Round = serde_json::from_str(jsonl.lines().next().unwrap()).unwrap();
+            assert_eq!(&read, round);
+        }
+    }
+
+    mod replay {
+        use super::common::*;
+        use crate::*;
+
+        // Plays the round again at a fresh table and hands back the events
+        // it produced along with everyone's bank at the end
+        fn replayed(round: &Round) -> (Vec<Event>, Vec<i32>) {
+            let mut dealer = Dealer::new();
+            let mut players: Vec<Player> = round
+                .seats
+                .iter()
+                .map(|seat| {
+                    let mut player = Player::new();
+                    player.bank = seat.bank;
+                    player
+                })
+                .collect();
+            let (first, rest) = players.split_first_mut().unwrap();
+            let mut game = Game::with_seed(round.rules, 99, &mut dealer, first);
+            for player in rest {
+                game.seat(player).unwrap();
+            }
+
+            game.restage(round).unwrap();
+            for i in 0..round.events.len() {
+                game.replay(round, i).unwrap();
+            }
+            let events = game.take_events();
+            let banks = (0..game.seats())
+                .map(|seat| game.player(seat).unwrap().bank)
+                .collect();
+            (events, banks)
+        }
+
+        fn without_shuffles(round: &Round) -> Vec<Event> {
+            round
+                .events
+                .iter()
+                .filter(|event| **event != Event::Shuffled)
+                .copied()
+                .collect()
+        }
+
+        #[test]
+        fn plays_back_the_same() {
+            let mut dealer = Dealer::new();
+            let mut players = [Player::new(), Player::new()];
+            let (first, rest) = players.split_first_mut().unwrap();
+            let mut game = Game::with_seed(TableRules::new(1), 4, &mut dealer, first);
+            game.seat(&mut rest[0]).unwrap();
+            stack(
+                &mut game,
+                &[
+                    Value::Eight,
+                    Value::Ten,
+                    Value::Ace,
+                    Value::Eight,
+                    Value::Nine,
+                    Value::Seven,
+                    Value::Three,
+                    Value::Two,
+                    Value::Ten,
+                    Value::Five,
+                ],
+            );
+
+            game.select_seat(0).unwrap();
+            game.place_bet(10).unwrap();
+            game.select_seat(1).unwrap();
+            game.place_bet(20).unwrap();
+            game.deal().unwrap();
+            game.decline_insurance().unwrap();
+            game.place_insurance(10).unwrap();
+            game.settle_insurance();
+            game.player_split().unwrap();
+            game.player_double().unwrap();
+            game.player_hit().unwrap();
+            game.player_stand().unwrap();
+            game.player_stand().unwrap();
+            game.dealer_reveal();
+            game.settle().unwrap();
+            let banks: Vec<i32> = (0..2).map(|seat| game.player(seat).unwrap().bank).collect();
+            game.clear().unwrap();
+
+            let round = game.history()[0].clone();
+            assert_eq!(round.cards()[5], card(Value::Seven));
+            let (events, replayed_banks) = replayed(&round);
+            assert_eq!(events, without_shuffles(&round));
+            assert_eq!(replayed_banks, banks);
+        }
+
+        #[test]
+        fn no_hole_card_until_the_end() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let mut rules = TableRules::new(1);
+            rules.hole_card = HoleCard::European;
+            let mut game = Game::with_seed(rules, 4, &mut dealer, &mut player);
+            let cards = [Value::Ten, Value::Six, Value::Six, Value::Ten, Value::Five];
+            stack(&mut game, &cards);
+
+            game.place_bet(10).unwrap();
+            game.deal().unwrap();
+            game.player_stand().unwrap();
+            game.dealer_reveal();
+            game.dealer_hit().unwrap();
+            game.settle().unwrap();
+            game.clear().unwrap();
+
+            let round = game.history()[0].clone();
+            let expected: Vec<Card> = cards.iter().map(|value| card(*value)).collect();
+            assert_eq!(round.cards(), expected);
+            let (events, banks) = replayed(&round);
+            assert_eq!(events, without_shuffles(&round));
+            assert_eq!(banks, vec![990]);
+        }
+
+        #[test]
+        fn moves_to_step_through() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let mut game = Game::with_seed(TableRules::new(1), 4, &mut dealer, &mut player);
+            stack(
+                &mut game,
+                &[Value::Ten, Value::Six, Value::Six, Value::Seven, Value::Two],
+            );
+            game.place_bet(10).unwrap();
+            game.deal().unwrap();
+            game.player_hit().unwrap();
+            game.player_stand().unwrap();
+            game.settle().unwrap();
+            game.clear().unwrap();
+
+            // The deal, the hit, the stand, the reveal, the dealer's card and
+            // the settlement. Cards that come with a move aren't moves.
+            let mut round = game.history()[0].clone();
+            assert_eq!(bj::replay::moves(&round), Ok(vec![0, 4, 6, 7, 8, 9]));
+            assert!(matches!(round.events[4], Event::Hit { .. }));
+            assert!(matches!(round.events[6], Event::Stood { .. }));
+
+            round.events[6] = Event::Stood { seat: 0, hand: 1 };
+            assert_eq!(
+                bj::replay::moves(&round),
+                Err("round 1 doesn't play back the same: it isn't seat 1's turn".to_string())
+            );
+        }
+
+        #[test]
+        fn moves_have_to_be_in_turn() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let mut game = Game::with_seed(TableRules::new(1), 4, &mut dealer, &mut player);
+            game.place_bet(10).unwrap();
+            game.deal().unwrap();
+            game.player_stand().unwrap();
+            game.settle().unwrap();
+            game.clear().unwrap();
+
+            let mut round = game.history()[0].clone();
+            let stood = round
+                .events
+                .iter()
+                .position(|event| matches!(event, Event::Stood { .. }))
+                .unwrap();
+            round.events[stood] = Event::Stood { seat: 0, hand: 1 };
+
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let mut game = Game::with_seed(round.rules, 4, &mut dealer, &mut player);
+            game.restage(&round).unwrap();
+            for i in 0..stood {
+                game.replay(&round, i).unwrap();
+            }
+            assert_eq!(game.replay(&round, stood), Err(Errs::OutOfTurn(0)));
+        }
+    }
+
+    mod stats {
+        use super::common::*;
+        use crate::*;
+        use std::time::Duration;
+
+        #[test]
+        fn time_adds_up_round_by_round() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            player.stats.time = Duration::from_secs(60);
+            let mut game = Game::with_seed(TableRules::new(1), 4, &mut dealer, &mut player);
+            stack(
+                &mut game,
+                &[Value::Ten, Value::Nine, Value::Eight, Value::Seven],
+            );
+            game.place_bet(10).unwrap();
+            game.deal().unwrap();
+            std::thread::sleep(Duration::from_millis(20));
+            finish(&mut game);
+
+            let time = game.player(0).unwrap().stats.time;
+            assert!(time >= Duration::from_millis(60_020));
+            assert!(time < Duration::from_secs(70));
+        }
+
+        #[test]
+        fn counts_every_hand() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let mut game = Game::with_seed(TableRules::new(1), 4, &mut dealer, &mut player);
+            // 8 8 against a 6 and 10 in the hole. The first hand gets a 3 and
+            // doubles into a 10 for 21, the second gets a 10 and busts on a 5.
+            stack(
+                &mut game,
+                &[
+                    Value::Eight,
+                    Value::Six,
+                    Value::Eight,
+                    Value::Ten,
+                    Value::Three,
+                    Value::Ten,
+                    Value::Ten,
+                    Value::Five,
+                ],
+            );
+
+            game.place_bet(10).unwrap();
+            game.deal().unwrap();
+            game.player_split().unwrap();
+            game.player_double().unwrap();
+            game.player_hit().unwrap();
+            game.settle().unwrap();
+
+            let stats = game.player(0).unwrap().stats;
+            assert_eq!(stats.hands, 2);
+            assert_eq!((stats.wins, stats.losses, stats.pushes), (1, 1, 0));
+            assert_eq!((stats.doubles, stats.splits, stats.busts), (1, 1, 1));
+            assert_eq!(stats.rounds, 1);
+            assert_eq!(stats.net, 10);
+            assert_eq!(stats.biggest_win, 10);
+        }
+
+        #[test]
+        fn insurance_counts_towards_the_round() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let mut game = Game::with_seed(TableRules::new(1), 4, &mut dealer, &mut player);
+            stack(
+                &mut game,
+                &[Value::Ten, Value::Ace, Value::Nine, Value::King],
+            );
+
+            game.place_bet(10).unwrap();
+            game.deal().unwrap();
+            game.place_insurance(5).unwrap();
+            game.settle_insurance();
+            game.settle().unwrap();
+
+            let stats = game.player(0).unwrap().stats;
+            assert_eq!((stats.losses, stats.blackjacks), (1, 0));
+            assert_eq!(stats.net, 0);
+        }
+
+        #[test]
+        fn drawdown_from_the_high_point() {
+            let mut stats = Stats::default();
+            for net in [20, -10, 30, -25, -15, 5] {
+                stats.record_round(net);
+            }
+            assert_eq!(stats.rounds, 6);
+            assert_eq!(stats.net, 5);
+            assert_eq!(stats.biggest_win, 30);
+            assert_eq!(stats.max_drawdown, 40);
+        }
+    }
+
+    mod saving {
+        use crate::*;
+
+        #[test]
+        fn rules_round_trip() {
+            let mut rules = TableRules::new(6);
+            rules.hole_card = HoleCard::European;
+            rules.blackjack_payout = Payout::SixToFive;
+            rules.surrender = Surrender::Late;
+            rules.double = Double::NineToEleven;
+
+            let json = serde_json::to_string(&rules).unwrap();
+            assert_eq!(serde_json::from_str::<TableRules>(&json).unwrap(), rules);
+        }
+
+        #[test]
+        fn shoe_picks_up_where_it_left_off() {
+            let mut shoe = Shoe::new(2, 0.6);
+            shoe.shuffle_seeded(3);
+            let dealt: Vec<Card> = (0..20).filter_map(|_| shoe.draw()).collect();
+            shoe.discard(dealt);
+
+            let json = serde_json::to_string(&shoe).unwrap();
+            let mut restored: Shoe = serde_json::from_str(&json).unwrap();
+            assert_eq!(restored.cards, shoe.cards);
+            assert_eq!(restored.discards, shoe.discards);
+            assert_eq!(restored.num_decks(), 2);
+            assert_eq!(restored.cut_card(), shoe.cut_card());
+            assert_eq!(restored.running_count(&HiLo), shoe.running_count(&HiLo));
+            assert_eq!(restored.draw(), shoe.draw());
+        }
+
+        #[test]
+        fn older_rules_get_the_new_fields() {
+            let mut json = serde_json::to_value(TableRules::new(6)).unwrap();
+            let fields = json.as_object_mut().unwrap();
+            fields.remove("min_bet");
+            fields.remove("max_bet");
+
+            let rules: TableRules = serde_json::from_value(json).unwrap();
+            assert_eq!(rules, TableRules::new(6));
+        }
+
+        fn played() -> Stats {
+            let mut stats = Stats::default();
+            stats.hands = 3;
+            stats.wins = 2;
+            stats.losses = 1;
+            stats.blackjacks = 1;
+            stats.time = std::time::Duration::from_secs(95);
+            stats.record_round(40);
+            stats.record_round(-25);
+            stats
+        }
+
+        #[test]
+        fn stats_round_trip() {
+            let stats = played();
+            let json = serde_json::to_string(&stats).unwrap();
+            assert_eq!(serde_json::from_str::<Stats>(&json).unwrap(), stats);
+        }
+
+        #[test]
+        fn stats_are_saved_with_the_players() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            player.stats = played();
+            let game = Game::new(TableRules::new(2), &mut dealer, &mut player);
+
+            let json = serde_json::to_string(&bj::save::Save::new(&game)).unwrap();
+            let save = bj::save::Save::parse(&json).unwrap();
+            assert_eq!(save.players[0].stats, played());
+            assert_eq!(save.players(1000)[0].stats, played());
+        }
+
+        #[test]
+        fn version_one_saves_start_with_no_stats() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            player.stats = played();
+            let game = Game::new(TableRules::new(2), &mut dealer, &mut player);
+
+            let mut json = serde_json::to_value(bj::save::Save::new(&game)).unwrap();
+            json["version"] = 1.into();
+            json["players"][0].as_object_mut().unwrap().remove("stats");
+
+            let save = bj::save::Save::parse(&json.to_string()).unwrap();
+            assert_eq!(save.version, bj::save::VERSION);
+            assert_eq!(save.players[0].stats, Stats::default());
+        }
+
+        fn saved() -> serde_json::Value {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let game = Game::with_seed(TableRules::new(2), 5, &mut dealer, &mut player);
+            serde_json::to_value(bj::save::Save::new(&game)).unwrap()
+        }
+
+        fn corrupt(json: serde_json::Value) -> String {
+            match bj::save::Save::parse(&json.to_string()) {
+                Err(bj::save::SaveError::Corrupt(e)) => e.to_string(),
+                Err(e) => panic!("expected a corrupt save, got: {}", e),
+                Ok(_) => panic!("a corrupt save was loaded"),
+            }
+        }
+
+        #[test]
+        fn a_good_save_loads() {
+            assert!(bj::save::Save::parse(&saved().to_string()).is_ok());
+        }
+
+        #[test]
+        fn no_players_is_corrupt() {
+            let mut json = saved();
+            json["players"] = serde_json::json!([]);
+            assert_eq!(corrupt(json), "there's nobody at the table");
+        }
+
+        #[test]
+        fn nil_cards_are_corrupt() {
+            let mut json = saved();
+            json["shoe"]["cards"][0] = serde_json::json!({
+                "value": "Nil",
+                "suit": "Nil",
+                "hidden": false,
+            });
+            assert_eq!(corrupt(json), "the shoe isn't made up of whole decks");
+        }
+
+        #[test]
+        fn missing_or_extra_cards_are_corrupt() {
+            let mut json = saved();
+            json["shoe"]["cards"].as_array_mut().unwrap().pop();
+            assert_eq!(corrupt(json), "the shoe isn't made up of whole decks");
+
+            let mut json = saved();
+            let card = json["shoe"]["cards"][0].clone();
+            json["shoe"]["cards"][1] = card;
+            assert_eq!(corrupt(json), "the shoe isn't made up of whole decks");
+        }
+    }
+
+    mod config {
+        use crate::*;
+        use bj::config::{Command, Settings, from_args};
+        use bj::save::Save;
+        use std::fs;
+        use std::path::PathBuf;
+
+        fn parse(args: &[&str]) -> Result<Command, String> {
+            from_args(args.iter().map(|arg| arg.to_string()).collect(), None)
+        }
+
+        fn settings(args: &[&str]) -> Settings {
+            match parse(args) {
+                Ok(Command::Play(settings)) => *settings,
+                Ok(_) => panic!("expected settings from {:?}", args),
+                Err(e) => panic!("{:?} was rejected: {}", args, e),
+            }
+        }
+
+        fn error(args: &[&str]) -> String {
+            match parse(args) {
+                Err(e) => e,
+                Ok(_) => panic!("{:?} should have been rejected", args),
+            }
+        }
+
+        fn file(name: &str, text: &str) -> PathBuf {
+            let path = std::env::temp_dir().join(format!(
+                "bj-config-{}-{}.toml",
+                std::process::id(),
+                name
+            ));
+            fs::write(&path, text).unwrap();
+            path
+        }
+
+        #[test]
+        fn defaults() {
+            let settings = settings(&[]);
+            assert_eq!(settings.profile, None);
+            assert_eq!(settings.players, None);
+            assert_eq!(settings.bank, 1000);
+            assert_eq!(settings.seed, None);
+            assert_eq!(settings.delay, 1);
+            assert!(!settings.warn);
+            assert_eq!(
+                settings.rules(TableRules::default()),
+                Ok(TableRules::default())
+            );
+        }
+
+        #[test]
+        fn commands() {
+            assert!(matches!(parse(&["--help"]), Ok(Command::Help)));
+            assert!(matches!(
+                parse(&["replay", "hands.txt"]),
+                Ok(Command::Replay(path)) if path == "hands.txt"
+            ));
+            assert_eq!(error(&["replay"]), "replay needs the file to play back");
+        }
+
+        #[test]
+        fn bad_flags() {
+            assert_eq!(error(&["--colour"]), "unknown option '--colour'");
+            assert_eq!(error(&["--decks"]), "--decks needs a value");
+            assert_eq!(
+                error(&["--decks", "six"]),
+                "'six' isn't a valid value for --decks"
+            );
+        }
+
+        #[test]
+        fn any_seed() {
+            assert_eq!(settings(&["--seed", "0"]).seed, Some(0));
+            assert_eq!(
+                settings(&["--seed", "18446744073709551615"]).seed,
+                Some(u64::MAX)
+            );
+            assert_eq!(
+                error(&["--seed", "-1"]),
+                "'-1' isn't a valid value for --seed"
+            );
+
+            let path = file("seed", "seed = \"18446744073709551615\"");
+            let big = settings(&["--config", path.to_str().unwrap()]).seed;
+            let path = file("small-seed", "seed = 42");
+            let small = settings(&["--config", path.to_str().unwrap()]).seed;
+            let path = file("negative-seed", "seed = -42");
+            let negative = error(&["--config", path.to_str().unwrap()]);
+            assert_eq!(big, Some(u64::MAX));
+            assert_eq!(small, Some(42));
+            assert!(negative.starts_with("seed has to be a whole number"));
+        }
+
+        #[test]
+        fn settings_are_checked() {
+            assert_eq!(
+                error(&["--players", "8"]),
+                "players has to be between 1 and 7, not 8"
+            );
+            assert_eq!(
+                error(&["--bank", "0"]),
+                "bank has to be between 1 and 2147483647, not 0"
+            );
+            assert_eq!(
+                error(&["--delay", "11"]),
+                "delay has to be between 0 and 10, not 11"
+            );
+            assert_eq!(
+                error(&["--name", "  "]),
+                "name has to be between 1 and 24 characters"
+            );
+            assert_eq!(
+                error(&["--trainer", "red-seven"]),
+                "there's no counting system called 'red-seven'"
+            );
+            assert!(error(&["--profile", "../etc"]).contains("../etc"));
+        }
+
+        #[test]
+        fn rules_are_checked_up_front() {
+            assert_eq!(
+                error(&["--decks", "9"]),
+                "decks has to be between 1 and 8, not 9"
+            );
+            assert_eq!(
+                error(&["--penetration", "1"]),
+                "penetration has to be at least 0 and less than 1"
+            );
+            assert_eq!(
+                error(&["--payout", "2:1"]),
+                "unknown payout '2:1', use 3:2, 6:5 or 1:1"
+            );
+            assert_eq!(
+                error(&["--min-bet", "10", "--max-bet", "5"]),
+                "the maximum bet ($5) is under the minimum ($10)"
+            );
+            assert_eq!(
+                error(&["--bank", "5", "--min-bet", "10"]),
+                "a $5 bank can't cover the $10 minimum bet"
+            );
+        }
+
+        #[test]
+        fn rules_go_on_top_of_the_table() {
+            let settings = settings(&[
+                "--decks",
+                "6",
+                "--h17",
+                "--european",
+                "--surrender",
+                "late",
+                "--no-das",
+                "--min-bet",
+                "5",
+            ]);
+            let mut base = TableRules::new(8);
+            base.blackjack_payout = Payout::SixToFive;
+            base.max_bet = 500;
+
+            let rules = settings.rules(base).unwrap();
+            assert_eq!(rules.num_decks, 6);
+            assert!(rules.dealer_hits_soft_17);
+            assert_eq!(rules.hole_card, HoleCard::European);
+            assert_eq!(rules.surrender, Surrender::Late);
+            assert!(!rules.double_after_split);
+            assert_eq!(rules.min_bet, 5);
+            // Anything not set stays as the table had it
+            assert_eq!(rules.blackjack_payout, Payout::SixToFive);
+            assert_eq!(rules.max_bet, 500);
+        }
+
+        #[test]
+        fn penetration_goes_on_top_of_a_saved_shoe() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let game = Game::with_seed(TableRules::new(2), 3, &mut dealer, &mut player);
+            let save = Save::new(&game);
+            assert_eq!(save.shoe.penetration(), 0.75);
+
+            let rules = settings(&["--penetration", "0.5"])
+                .table(Some(&save))
+                .unwrap();
+            assert_eq!(rules.penetration, 0.5);
+
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let mut game = Game::with_seed(rules, 4, &mut dealer, &mut player);
+            assert!(save.restore_shoe(&mut game));
+            assert_eq!(game.shoe.cards, save.shoe.cards);
+            assert_eq!(game.shoe.penetration(), 0.5);
+            assert_eq!(game.shoe.cut_card(), 52);
+        }
+
+        #[test]
+        fn rules_are_checked_against_the_table() {
+            let settings = settings(&["--min-bet", "50"]);
+            let mut base = TableRules::new(2);
+            base.max_bet = 20;
+            assert_eq!(
+                settings.rules(base),
+                Err("the maximum bet ($20) is under the minimum ($50)".to_string())
+            );
+        }
+
+        #[test]
+        fn flags_beat_the_file() {
+            let path = file("flags", "decks = 4\nwarn = true\npayout = \"6:5\"");
+            let settings = settings(&["--config", path.to_str().unwrap(), "--decks", "1"]);
+            let rules = settings.rules(TableRules::default()).unwrap();
+            assert_eq!(rules.num_decks, 1);
+            assert_eq!(rules.blackjack_payout, Payout::SixToFive);
+            assert!(settings.warn);
+        }
+
+        #[test]
+        fn config_files() {
+            let missing = std::env::temp_dir().join("bj-config-that-isnt-there.toml");
+            let args = vec!["--decks".to_string(), "1".to_string()];
+            assert!(from_args(args, Some(missing.clone())).is_ok());
+            assert!(
+                error(&["--config", missing.to_str().unwrap()])
+                    .starts_with(&format!("can't read {}", missing.display()))
+            );
+
+            let path = file("unknown", "decks = 2\ncolour = \"red\"");
+            let e = error(&["--config", path.to_str().unwrap()]);
+            assert!(e.starts_with(&path.display().to_string()));
+            assert!(e.contains("colour"));
+        }
+
+        #[test]
+        fn saved_tables_keep_their_players() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let mut rules = TableRules::new(6);
+            rules.min_bet = 10;
+            let game = Game::new(rules, &mut dealer, &mut player);
+            let save = Save::new(&game);
+
+            let table = settings(&["--h17"]).table(Some(&save)).unwrap();
+            assert_eq!(table.num_decks, 6);
+            assert_eq!(table.min_bet, 10);
+            assert!(table.dealer_hits_soft_17);
+
+            assert!(settings(&["--players", "3"]).table(Some(&save)).is_err());
+            assert!(settings(&["--name", "Ada"]).table(Some(&save)).is_err());
+            assert!(settings(&["--players", "3"]).table(None).is_ok());
+        }
+    }
+
+    mod trainer {
+        use crate::*;
+        use bj::trainer::{Question, Score, Trainer, answer};
+        use std::time::Duration;
+
+        fn schedule(system: &'static dyn CountingSystem) -> Vec<Option<Question>> {
+            let mut trainer = Trainer::new(system);
+            (0..12).map(|_| trainer.next_question()).collect()
+        }
+
+        #[test]
+        fn every_third_round_alternating() {
+            use Question::*;
+            assert_eq!(
+                schedule(&HiLo),
+                [
+                    None,
+                    None,
+                    Some(Running),
+                    None,
+                    None,
+                    Some(True),
+                    None,
+                    None,
+                    Some(Running),
+                    None,
+                    None,
+                    Some(True),
+                ]
+            );
+        }
+
+        #[test]
+        fn no_true_count_for_unbalanced_systems() {
+            assert!(!KnockOut.balanced());
+            for system in SYSTEMS {
+                let asked: Vec<Question> = schedule(system).into_iter().flatten().collect();
+                assert_eq!(asked.len(), 4);
+                assert_eq!(
+                    asked.contains(&Question::True),
+                    system.balanced(),
+                    "{}",
+                    system.name()
+                );
+            }
+        }
+
+        #[test]
+        fn running_count_has_to_be_exact() {
+            assert!(Question::Running.right(-3.0, -3.0));
+            assert!(Question::Running.right(1.5, 1.5));
+            assert!(!Question::Running.right(2.0, 1.5));
+            assert!(!Question::Running.right(-3.0, 3.0));
+        }
+
+        #[test]
+        fn true_count_within_half() {
+            assert!(Question::True.right(2.0, 2.4));
+            assert!(Question::True.right(2.0, 2.5));
+            assert!(Question::True.right(-1.0, -1.5));
+            assert!(!Question::True.right(2.0, 2.6));
+            assert!(!Question::True.right(0.0, -0.75));
+        }
+
+        #[test]
+        fn answers() {
+            assert_eq!(answer("+3\n"), Some(3.0));
+            assert_eq!(answer(" -2 "), Some(-2.0));
+            assert_eq!(answer("1.5"), Some(1.5));
+            assert_eq!(answer(""), None);
+            assert_eq!(answer("three"), None);
+        }
+
+        #[test]
+        fn scoring() {
+            let mut score = Score::default();
+            assert_eq!(score.line("Running count"), None);
+
+            score.record(true, Duration::from_secs(2));
+            score.record(false, Duration::from_secs(4));
+            score.record(true, Duration::from_secs(3));
+            score.record(true, Duration::from_secs(3));
+            assert_eq!(
+                score.line("Running count").unwrap(),
+                "Running count: 3/4 right (75%), 3.0s per answer"
+            );
+        }
+    }
+
+    mod hand_value {
+        use super::common::*;
+        use crate::*;
+
+        #[test]
+        fn soft_and_hard_17() {
+            let soft = HandValue::new(&cards(&[Value::Ace, Value::Six])).unwrap();
+            let hard = HandValue::new(&cards(&[Value::Ten, Value::Seven])).unwrap();
+
+            assert_eq!((soft.total, soft.is_soft), (17, true));
+            assert_eq!((hard.total, hard.is_soft), (17, false));
+        }
+
+        #[test]
+        fn aces_drop_to_one() {
+            let value = HandValue::new(&cards(&[Value::Ace, Value::Ace, Value::Nine])).unwrap();
+            assert_eq!((value.total, value.is_soft), (21, true));
+
+            let value = HandValue::new(&cards(&[Value::Ace, Value::Six, Value::Ten])).unwrap();
+            assert_eq!((value.total, value.is_soft), (17, false));
+        }
+
+        #[test]
+        fn blackjack_bust_and_pairs() {
+            let blackjack = HandValue::new(&cards(&[Value::Ace, Value::Queen])).unwrap();
+            let bust = HandValue::new(&cards(&[Value::King, Value::Queen, Value::Two])).unwrap();
+            let pair = HandValue::new(&cards(&[Value::Eight, Value::Eight])).unwrap();
+            let faces = HandValue::new(&cards(&[Value::King, Value::Queen])).unwrap();
+
+            assert!(blackjack.is_blackjack && !blackjack.is_bust);
+            assert!(bust.is_bust && !bust.is_blackjack);
+            assert!(pair.is_pair);
+            assert!(!faces.is_pair);
+        }
+
+        #[test]
+        fn split_21_is_not_blackjack() {
+            let mut hand = Hand::new(cards(&[Value::Ace, Value::King]), 10);
+            assert!(hand.value().unwrap().is_blackjack);
+
+            hand.split = true;
+            assert!(!hand.value().unwrap().is_blackjack);
+            assert_eq!(hand.value().unwrap().total, 21);
+        }
+
+        #[test]
+        fn nil_cards_are_rejected() {
+            assert!(HandValue::new(&cards(&[Value::Nil])).is_err());
+        }
+    }
+
+    mod errors {
+        use crate::*;
+
+        #[test]
+        fn empty_deck_does_not_panic() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let mut deck = vec![Card::new(Value::Two, Suit::Clubs, false)];
+
+            assert_eq!(dealer.deal(&mut deck), Err(Errs::DeckExhausted));
+            assert_eq!(player.deal(&mut deck), Err(Errs::DeckExhausted));
+            assert_eq!(deck.len(), 1);
+
+            player.hit(&mut deck).unwrap();
+            assert_eq!(player.hit(&mut deck), Err(Errs::DeckExhausted));
+        }
+
+        #[test]
+        fn empty_shoe_does_not_panic() {
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let mut game = Game::with_seed(TableRules::new(1), 2, &mut dealer, &mut player);
+            game.shoe.cards.truncate(3);
+
+            assert_eq!(game.deal(), Err(Errs::DeckExhausted));
+            assert_eq!(game.phase(), Phase::Betting);
+        }
+
+        #[test]
+        fn messages() {
+            let nil = Card::new(Value::Nil, Suit::Nil, false);
+            let illegal = Errs::IllegalAction {
+                phase: Phase::DealerTurn,
+                action: Action::Hit,
+            };
+
+            assert_eq!(
+                Errs::InsufficientFunds { bank: 5, bet: 10 }.to_string(),
+                "can't bet $10 with only $5 in the bank"
+            );
+            assert_eq!(illegal.to_string(), "can't hit during the dealer's turn");
+            assert_eq!(
+                HandValue::new(&[nil]).unwrap_err().to_string(),
+                "Nil of Nil isn't a card that can be played"
+            );
+
+            let boxed: Box<dyn std::error::Error> = Box::new(Errs::DeckExhausted);
+            assert_eq!(boxed.to_string(), "the shoe has run out of cards");
+        }
+    }
+
+    mod deck {
+        mod suits {
+            use crate::*;
+
+            #[test]
+            fn removing_cards_when_dealt() {
+                let mut dealer = Dealer::new();
+                let mut player = Player::new();
+                let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+
+                game.deal().unwrap();
+
+                assert_eq!(game.shoe.cards.len(), 48);
+            }
+
+            #[test]
+            fn count_hearts() {
+                let deck = Deck::new(1);
+                let count = deck
+                    .cards
+                    .iter()
+                    .filter(|card| card.suit == Suit::Hearts)
+                    .count();
+
+                assert_eq!(count, 13);
+            }
+
+            #[test]
+            fn count_spades() {
+                let deck = Deck::new(1);
+                let count = deck
+                    .cards
+                    .iter()
+                    .filter(|card| card.suit == Suit::Spades)
+                    .count();
+
+                assert_eq!(count, 13);
+            }
+
+            #[test]
+            fn count_diamonds() {
+                let deck = Deck::new(1);
+                let count = deck
+                    .cards
+                    .iter()
+                    .filter(|card| card.suit == Suit::Diamonds)
+                    .count();
+
+                assert_eq!(count, 13);
+            }
+
+            #[test]
+            fn count_clubs() {
+                let deck = Deck::new(1);
+                let count = deck
+                    .cards
+                    .iter()
+                    .filter(|card| card.suit == Suit::Clubs)
+                    .count();
+
+                assert_eq!(count, 13);
+            }
+        }
+
+        mod shuffle {
+            use crate::*;
+
+            #[test]
+            fn same_seed_same_order() {
+                let mut a = Deck::new(2);
+                let mut b = Deck::new(2);
+                a.shuffle_seeded(42);
+                b.shuffle_seeded(42);
+
+                assert_eq!(a.cards, b.cards);
+            }
+
+            #[test]
+            fn different_seed_different_order() {
+                let mut a = Deck::new(2);
+                let mut b = Deck::new(2);
+                a.shuffle_seeded(1);
+                b.shuffle_seeded(2);
+
+                assert_ne!(a.cards, b.cards);
+            }
+
+            #[test]
+            fn keeps_every_card() {
+                let mut deck = Deck::new(1);
+                deck.shuffle_seeded(7);
+
+                assert_eq!(deck.cards.len(), 52);
+                assert!(!deck.cards.iter().any(|card| card.value == Value::Nil));
+            }
+
+            #[test]
+            fn in_place() {
+                let mut deck = Deck::new(8);
+                let before = deck.cards.as_ptr();
+                deck.shuffle_seeded(7);
+
+                assert_eq!(deck.cards.as_ptr(), before);
+                assert_eq!(deck.cards.len(), 52 * 8);
+                for suit in [Suit::Hearts, Suit::Clubs, Suit::Diamonds, Suit::Spades] {
+                    let count = deck.cards.iter().filter(|card| card.suit == suit).count();
+                    assert_eq!(count, 13 * 8);
+                }
+            }
+
+            #[test]
+            fn game_with_seed_replays_deal() {
+                let mut dealer_a = Dealer::new();
+                let mut player_a = Player::new();
+                let mut game_a =
+                    Game::with_seed(TableRules::new(2), 1234, &mut dealer_a, &mut player_a);
+                game_a.deal().unwrap();
+                assert_eq!(game_a.seed(), 1234);
+
+                let mut dealer_b = Dealer::new();
+                let mut player_b = Player::new();
+                let mut game_b =
+                    Game::with_seed(TableRules::new(2), 1234, &mut dealer_b, &mut player_b);
+                game_b.deal().unwrap();
+
+                assert_eq!(game_a.shoe.cards, game_b.shoe.cards);
+                assert_eq!(dealer_a.hand, dealer_b.hand);
+                assert_eq!(player_a.hands, player_b.hands);
+            }
+        }
+
+        mod shoe {
+            use crate::tests::common::finish;
+            use crate::*;
+
+            #[test]
+            fn cut_card_position() {
+                let shoe = Shoe::new(6, 0.75);
+
+                assert_eq!(shoe.size(), 312);
+                assert_eq!(shoe.cut_card(), 78);
+                assert!(!shoe.needs_reshuffle());
+            }
+
+            #[test]
+            fn signals_reshuffle_past_cut_card() {
+                let mut shoe = Shoe::new(1, 0.5);
+
+                for _ in 0..25 {
+                    shoe.draw();
+                }
+                assert!(!shoe.needs_reshuffle());
+
+                shoe.draw();
+                assert!(shoe.needs_reshuffle());
+            }
+
+            #[test]
+            fn reshuffle_collects_discards() {
+                let mut shoe = Shoe::new(1, 0.75);
+                let mut dealt = Vec::new();
+                for _ in 0..10 {
+                    dealt.push(shoe.draw().unwrap());
+                }
+                dealt[0].set_hidden(true);
+                shoe.discard(dealt);
+
+                assert_eq!(shoe.cards_left(), 42);
+                assert_eq!(shoe.discards.len(), 10);
+
+                shoe.shuffle_seeded(3);
+
+                assert_eq!(shoe.cards_left(), 52);
+                assert!(shoe.discards.is_empty());
+                assert!(!shoe.cards.iter().any(|card| card.hidden));
+            }
+
+            #[test]
+            fn game_does_not_shuffle_mid_shoe() {
+                let mut dealer = Dealer::new();
+                let mut player = Player::new();
+                let mut game = Game::with_seed(TableRules::new(1), 9, &mut dealer, &mut player);
+
+                game.deal().unwrap();
+                finish(&mut game);
+                let next = game.shoe.cards.clone();
+                game.clear().unwrap();
+                game.deal().unwrap();
+
+                assert_eq!(game.shoe.cards[..], next[..next.len() - 4]);
+                assert_eq!(game.shoe.discards.len(), 52 - next.len());
+            }
+
+            #[test]
+            fn game_reshuffles_after_cut_card() {
+                let mut dealer = Dealer::new();
+                let mut player = Player::new();
+                let mut game = Game::with_seed(TableRules::new(1), 9, &mut dealer, &mut player);
+
+                while !game.needs_reshuffle() {
+                    game.deal().unwrap();
+                    finish(&mut game);
+                    game.clear().unwrap();
+                }
+                game.deal().unwrap();
+
+                assert_eq!(game.shoe.cards_left(), 48);
+                assert!(game.shoe.discards.is_empty());
+            }
+        }
+    }
+
+    mod rules {
+        use super::common::*;
+        use crate::*;
+
+        #[test]
+        fn table_limits() {
+            let rules = TableRules {
+                min_bet: 10,
+                max_bet: 500,
+                ..TableRules::new(1)
+            };
+            let mut dealer = Dealer::new();
+            let mut player = Player::new();
+            let mut game = Game::new(rules, &mut dealer, &mut player);
+
+            let under = Errs::OutsideLimits {
+                bet: 5,
+                min: 10,
+                max: 500,
+            };
+            assert_eq!(game.place_bet(5), Err(under));
+            assert_eq!(
+                under.to_string(),
+                "a bet of $5 isn't allowed, bets here are $10 to $500"
+            );
+            assert!(matches!(
+                game.place_bet(501),
+                Err(Errs::OutsideLimits { bet: 501, .. })
+            ));
+            assert_eq!(game.place_bet(10), Ok(()));
+            assert_eq!(game.place_bet(500), Ok(()));
+            assert_eq!(game.get_bank(), 490);
+        }
+
+        fn soft_17_dealer() -> Dealer {
+            dealer(Value::Ace, Value::Six)
+        }
+
+        #[test]
+        fn dealer_stands_on_soft_17() {
+            let mut dealer = soft_17_dealer();
+            let mut player = Player::new();
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+
+            assert_eq!(game.dealer_checks(), Ok(HandStatus::Live(17)));
+            assert_eq!(dealer.hand.len(), 1);
+        }
+
+        #[test]
+        fn dealer_hits_soft_17() {
+            let rules = TableRules {
+                dealer_hits_soft_17: true,
+                ..TableRules::new(1)
+            };
+            let mut dealer = soft_17_dealer();
+            let mut player = Player::new();
+            let mut game = Game::new(rules, &mut dealer, &mut player);
+
+            let _ = game.dealer_checks();
+            assert!(dealer.hand.len() > 1);
+        }
+
+        #[test]
+        fn blackjack_payouts() {
+            for (payout, bank) in [
+                (Payout::ThreeToTwo, 115),
+                (Payout::SixToFive, 112),
+                (Payout::EvenMoney, 110),
+            ] {
+                let rules = TableRules {
+                    blackjack_payout: payout,
+                    ..TableRules::new(1)
+                };
+                let mut dealer = dealer(Value::Ten, Value::Seven);
+                let mut player = player(&[Value::Ace, Value::King], 100);
+                let mut game = Game::new(rules, &mut dealer, &mut player);
+
+                game.remove_bank(10);
+                game.player_stand().unwrap();
+                let results = game.settle().unwrap();
+                assert_eq!(results[0].outcome, RoundOutcome::Blackjack);
+                assert_eq!(game.get_bank(), bank, "{:?}", payout);
+            }
+        }
+
+        #[test]
+        fn five_card_charlie_off() {
+            let rules = TableRules {
+                five_card_charlie: false,
+                ..TableRules::new(1)
+            };
+            let hand = vec![
+                card(Value::Two),
+                card(Value::Two),
+                card(Value::Three),
+                card(Value::Three),
+                card(Value::Four),
+            ];
+            let mut dealer = Dealer::new();
+            let mut player = <Player as User>::from(hand, None, Some(30)).unwrap();
+            let mut game = Game::new(rules, &mut dealer, &mut player);
+
+            assert_eq!(game.player_checks(), Ok(HandStatus::Live(14)));
+        }
+
+        #[test]
+        fn five_card_bust_is_not_charlie() {
+            let hand = vec![
+                card(Value::Two),
+                card(Value::Three),
+                card(Value::Four),
+                card(Value::Five),
+                card(Value::King),
+            ];
+            let mut dealer = Dealer::new();
+            let mut player = <Player as User>::from(hand, None, Some(30)).unwrap();
+            let mut game = Game::new(TableRules::new(1), &mut dealer, &mut player);
+
+            assert_eq!(game.player_checks(), Ok(HandStatus::Bust));
+        }
+
+        #[test]
+        fn no_insurance_offered() {
+            let rules = TableRules {
+                insurance: false,
+                ..TableRules::new(1)
+            };
+            let mut dealer = soft_17_dealer();
+            let mut player = Player::new();
+            let game = Game::new(rules, &mut dealer, &mut player);
+
+            assert!(!game.can_insure());
+        }
     }
 
     mod values {
@@ -406,7 +3361,7 @@ mod tests {
         #[test]
         fn test_card_number() {
             let deck = Deck::new(3);
-            let count = deck.cards.iter().count();
+            let count = deck.cards.len();
 
             assert_eq!(count, 52 * 3);
         }